@@ -0,0 +1,42 @@
+use chess_board::ChessBoard;
+use chess_move::Move;
+use searcher::{Searcher, SearchLimits};
+
+pub const DEFAULT_BENCH_DEPTH: usize = 6;
+
+static BENCH_POSITIONS: [&str; 10] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+    "r2q1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP2BPPP/R2QK2R w KQ - 0 9",
+    "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1",
+    "8/8/4k3/3p4/3P4/4K3/8/8 w - - 0 1",
+];
+
+// Searches a fixed set of positions to a fixed depth, the node total changes whenever the
+// search does, so it doubles as a quick regression check. Each position is reported as soon as
+// it is done and the node total is returned.
+pub fn run_bench<F>(depth: usize, mut report_position: F) -> u64 where F: FnMut(&str, Option<Move>, u64) {
+    let mut total_nodes = 0;
+
+    for fen_string in BENCH_POSITIONS.iter() {
+        let current_position = ChessBoard::from_fen(fen_string).expect("Error: Invalid bench position!!!");
+        let mut searcher = Searcher::new();
+
+        let search_limits = SearchLimits {
+            depth: Some(depth),
+            ..SearchLimits::default()
+        };
+
+        searcher.run_iterative_deepening(&current_position, &search_limits, |_| {});
+
+        report_position(fen_string, searcher.get_best_move(), searcher.get_nodes());
+        total_nodes += searcher.get_nodes();
+    }
+
+    total_nodes
+}
@@ -0,0 +1,55 @@
+extern crate arsenic_chess_engine;
+
+use std::env;
+use std::process;
+use std::time::Instant;
+use arsenic_chess_engine::{ChessBoard, Color, MoveGenerator, Searcher, get_game_result, MAX_SEARCH_DEPTH};
+
+const DEFAULT_SEARCH_DEPTH: usize = 5;
+
+// Lets the engine play a whole game against itself from the initial position, printing the board
+// after every move. An optional argument sets how deep each move is searched.
+fn main() {
+    let depth = match env::args().nth(1) {
+        Some(depth) => depth.parse().unwrap_or(0),
+        None        => DEFAULT_SEARCH_DEPTH,
+    };
+
+    if !(1..=MAX_SEARCH_DEPTH).contains(&depth) {
+        eprintln!("Usage: selfplay [depth], where depth is a number from 1 to {}", MAX_SEARCH_DEPTH);
+        process::exit(1);
+    }
+
+    let start_time = Instant::now();
+
+    let mut searcher = Searcher::new();
+    let move_generator = MoveGenerator::new();
+
+    let mut board = ChessBoard::new();
+    board.print_board_from_white_perspective(true);
+
+    loop {
+        let legal_moves = move_generator.generate_next_moves_from_board(&mut board);
+
+        if let Some(game_result) = get_game_result(&board, &legal_moves, &move_generator) {
+            println!("{}", game_result);
+
+            break;
+        }
+
+        searcher.run_alpha_beta_pruning(&board, depth);
+
+        // The game would have ended above if there was no legal move to find.
+        let best_move = searcher.get_best_move().expect("Error: Search found no move!!!");
+
+        match board.get_side_to_move() {
+            Color::White => println!("White moves: {}", best_move),
+            Color::Black => println!("Black moves: {}", best_move),
+        }
+
+        board.make_move(best_move);
+        board.print_board_from_white_perspective(true);
+    }
+
+    println!("Time elapsed: {} s", start_time.elapsed().as_secs());
+}
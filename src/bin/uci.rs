@@ -0,0 +1,5 @@
+extern crate arsenic_chess_engine;
+
+fn main() {
+    arsenic_chess_engine::uci_loop();
+}
@@ -0,0 +1,287 @@
+use std::sync::OnceLock;
+
+pub type Bitboard = u64;
+
+pub const FILE_A: Bitboard = 0x0101_0101_0101_0101;
+pub const FILE_H: Bitboard = FILE_A << 7;
+pub const RANK_1: Bitboard = 0xff;
+pub const RANK_8: Bitboard = RANK_1 << 56;
+
+static ROOK_DIRECTIONS: [(i64, i64); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+static BISHOP_DIRECTIONS: [(i64, i64); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+static KNIGHT_JUMPS: [(i64, i64); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+static KING_STEPS: [(i64, i64); 8] = [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+
+pub fn square_bitboard(square: usize) -> Bitboard {
+    1 << square
+}
+
+pub fn get_squares(bitboard: Bitboard) -> BitboardSquares {
+    BitboardSquares {
+        bitboard,
+    }
+}
+
+pub fn get_file_bitboard(square: usize) -> Bitboard {
+    FILE_A << (square % 8)
+}
+
+pub fn get_adjacent_files_bitboard(square: usize) -> Bitboard {
+    let file = get_file_bitboard(square);
+
+    ((file << 1) & !FILE_A) | ((file >> 1) & !FILE_H)
+}
+
+// All ranks in front of the square as seen by the given side, not including its own rank.
+pub fn get_forward_ranks_bitboard(color: bool, square: usize) -> Bitboard {
+    let rank = (square / 8) as u32;
+
+    if color {
+        u64::MAX.checked_shl(8 * (rank + 1)).unwrap_or(0)
+    } else {
+        (1 << (8 * rank)) - 1
+    }
+}
+
+pub struct BitboardSquares {
+    bitboard: Bitboard,
+}
+
+impl Iterator for BitboardSquares {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.bitboard == 0 {
+            return None;
+        }
+
+        let square = self.bitboard.trailing_zeros() as usize;
+        self.bitboard &= self.bitboard - 1;
+
+        Some(square)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Magic {
+    mask: Bitboard,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn get_index(&self, occupancy: Bitboard) -> usize {
+        self.offset + ((occupancy & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+pub struct AttackTables {
+    knight_attacks: [Bitboard; 64],
+    king_attacks: [Bitboard; 64],
+    white_pawn_attacks: [Bitboard; 64],
+    black_pawn_attacks: [Bitboard; 64],
+    bishop_magics: [Magic; 64],
+    rook_magics: [Magic; 64],
+    sliding_attacks: Vec<Bitboard>,
+    squares_between: Vec<Bitboard>,
+}
+
+pub fn get_attack_tables() -> &'static AttackTables {
+    static ATTACK_TABLES: OnceLock<AttackTables> = OnceLock::new();
+
+    ATTACK_TABLES.get_or_init(AttackTables::new)
+}
+
+impl AttackTables {
+    fn new() -> AttackTables {
+        let mut attack_tables = AttackTables {
+            knight_attacks: [0; 64],
+            king_attacks: [0; 64],
+            white_pawn_attacks: [0; 64],
+            black_pawn_attacks: [0; 64],
+            bishop_magics: [Magic::default(); 64],
+            rook_magics: [Magic::default(); 64],
+            sliding_attacks: Vec::new(),
+            squares_between: vec![0; 64 * 64],
+        };
+
+        for square in 0..64 {
+            attack_tables.knight_attacks[square] = generate_step_attacks(square, &KNIGHT_JUMPS);
+            attack_tables.king_attacks[square] = generate_step_attacks(square, &KING_STEPS);
+            attack_tables.white_pawn_attacks[square] = generate_step_attacks(square, &[(-1, 1), (1, 1)]);
+            attack_tables.black_pawn_attacks[square] = generate_step_attacks(square, &[(-1, -1), (1, -1)]);
+        }
+
+        let mut random_state = 0x2545_f491_4f6c_dd1d;
+
+        for square in 0..64 {
+            attack_tables.bishop_magics[square] = attack_tables.find_magic(square, &BISHOP_DIRECTIONS, &mut random_state);
+            attack_tables.rook_magics[square] = attack_tables.find_magic(square, &ROOK_DIRECTIONS, &mut random_state);
+        }
+
+        for from in 0..64 {
+            for &direction in ROOK_DIRECTIONS.iter().chain(BISHOP_DIRECTIONS.iter()) {
+                let mut between = 0;
+                let (mut file, mut rank) = (from as i64 % 8 + direction.0, from as i64 / 8 + direction.1);
+
+                while (0..8).contains(&file) && (0..8).contains(&rank) {
+                    let to = (rank * 8 + file) as usize;
+
+                    attack_tables.squares_between[from * 64 + to] = between;
+                    between |= square_bitboard(to);
+
+                    file += direction.0;
+                    rank += direction.1;
+                }
+            }
+        }
+
+        attack_tables
+    }
+
+    // Searches for a multiplier that maps every relevant blocker subset to its own slot (or to
+    // a slot with identical attacks), so sliding attacks become a single table lookup.
+    fn find_magic(&mut self, square: usize, directions: &[(i64, i64)], random_state: &mut u64) -> Magic {
+        let mask = generate_sliding_attacks(square, 0, directions, true);
+        let relevant_bits = mask.count_ones();
+
+        let mut occupancies = Vec::new();
+        let mut attacks = Vec::new();
+        let mut occupancy: Bitboard = 0;
+
+        loop {
+            occupancies.push(occupancy);
+            attacks.push(generate_sliding_attacks(square, occupancy, directions, false));
+
+            occupancy = occupancy.wrapping_sub(mask) & mask;
+
+            if occupancy == 0 {
+                break;
+            }
+        }
+
+        let mut table = vec![0; 1 << relevant_bits];
+
+        loop {
+            let magic = next_random(random_state) & next_random(random_state) & next_random(random_state);
+
+            if (mask.wrapping_mul(magic) >> 56).count_ones() < 6 {
+                continue;
+            }
+
+            let candidate = Magic {
+                mask,
+                magic,
+                shift: 64 - relevant_bits,
+                offset: 0,
+            };
+
+            for entry in table.iter_mut() {
+                *entry = 0;
+            }
+
+            // Sliders always attack at least one square, so an empty slot is never a valid entry.
+            let collision_free = occupancies.iter().zip(attacks.iter()).all(|(&occupancy, &attack)| {
+                let index = candidate.get_index(occupancy);
+
+                if table[index] == 0 {
+                    table[index] = attack;
+                }
+
+                table[index] == attack
+            });
+
+            if collision_free {
+                let offset = self.sliding_attacks.len();
+                self.sliding_attacks.extend_from_slice(&table);
+
+                return Magic {
+                    offset,
+                    ..candidate
+                };
+            }
+        }
+    }
+
+    pub fn get_knight_attacks(&self, square: usize) -> Bitboard {
+        self.knight_attacks[square]
+    }
+
+    pub fn get_king_attacks(&self, square: usize) -> Bitboard {
+        self.king_attacks[square]
+    }
+
+    pub fn get_pawn_attacks(&self, color: bool, square: usize) -> Bitboard {
+        if color {
+            self.white_pawn_attacks[square]
+        } else {
+            self.black_pawn_attacks[square]
+        }
+    }
+
+    pub fn get_bishop_attacks(&self, square: usize, occupancy: Bitboard) -> Bitboard {
+        self.sliding_attacks[self.bishop_magics[square].get_index(occupancy)]
+    }
+
+    pub fn get_rook_attacks(&self, square: usize, occupancy: Bitboard) -> Bitboard {
+        self.sliding_attacks[self.rook_magics[square].get_index(occupancy)]
+    }
+
+    pub fn get_queen_attacks(&self, square: usize, occupancy: Bitboard) -> Bitboard {
+        self.get_bishop_attacks(square, occupancy) | self.get_rook_attacks(square, occupancy)
+    }
+
+    // Squares strictly between two squares on a shared line, empty if they are not aligned.
+    pub fn get_squares_between(&self, from: usize, to: usize) -> Bitboard {
+        self.squares_between[from * 64 + to]
+    }
+}
+
+fn generate_step_attacks(square: usize, steps: &[(i64, i64)]) -> Bitboard {
+    let (file, rank) = (square as i64 % 8, square as i64 / 8);
+
+    steps.iter()
+        .map(|&(file_step, rank_step)| (file + file_step, rank + rank_step))
+        .filter(|&(file, rank)| (0..8).contains(&file) && (0..8).contains(&rank))
+        .fold(0, |attacks, (file, rank)| attacks | square_bitboard((rank * 8 + file) as usize))
+}
+
+// With `relevant_only` the last square of every ray is left out, since a blocker there never
+// changes which squares are attacked.
+fn generate_sliding_attacks(square: usize, occupancy: Bitboard, directions: &[(i64, i64)], relevant_only: bool) -> Bitboard {
+    let mut attacks = 0;
+
+    for &(file_step, rank_step) in directions {
+        let (mut file, mut rank) = (square as i64 % 8 + file_step, square as i64 / 8 + rank_step);
+
+        while (0..8).contains(&file) && (0..8).contains(&rank) {
+            let next_file_on_board = (0..8).contains(&(file + file_step));
+            let next_rank_on_board = (0..8).contains(&(rank + rank_step));
+
+            if relevant_only && !(next_file_on_board && next_rank_on_board) {
+                break;
+            }
+
+            let target = square_bitboard((rank * 8 + file) as usize);
+            attacks |= target;
+
+            if occupancy & target != 0 {
+                break;
+            }
+
+            file += file_step;
+            rank += rank_step;
+        }
+    }
+
+    attacks
+}
+
+fn next_random(random_state: &mut u64) -> u64 {
+    *random_state ^= *random_state >> 12;
+    *random_state ^= *random_state << 25;
+    *random_state ^= *random_state >> 27;
+
+    random_state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+}
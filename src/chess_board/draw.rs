@@ -0,0 +1,106 @@
+use chess_board::{ChessBoard, PAWN, KNIGHT, BISHOP, ROOK, QUEEN};
+
+static LIGHT_SQUARES: u64 = 0x55aa_55aa_55aa_55aa;
+
+impl ChessBoard {
+    /// Counts earlier occurrences of the current position, which can only lie an even number of
+    /// plies back and not before the last capture or pawn move.
+    pub fn count_repetitions(&self) -> usize {
+        self.undo_stack.iter()
+            .rev()
+            .take(self.halfmove_clock)
+            .skip(1)
+            .step_by(2)
+            .filter(|undo_state| undo_state.hash_key == self.hash_key)
+            .count()
+    }
+
+    /// Whether fifty moves by each side have gone by without a capture or pawn move.
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    /// Neither side can mate with a lone minor piece, nor with any number of bishops that all stand
+    /// on squares of the same colour.
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy_pieces = self.piece_bitboards[PAWN] | self.piece_bitboards[ROOK] | self.piece_bitboards[QUEEN];
+
+        if heavy_pieces != 0 {
+            return false;
+        }
+
+        let knights = self.piece_bitboards[KNIGHT];
+        let bishops = self.piece_bitboards[BISHOP];
+
+        match (knights.count_ones(), bishops.count_ones()) {
+            (0, 0) | (1, 0) | (0, 1) => true,
+            (0, _) => bishops & LIGHT_SQUARES == 0 || bishops & !LIGHT_SQUARES == 0,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod draw_detection_tests {
+    use chess_board::ChessBoard;
+    use move_generator::MoveGenerator;
+    use test_util::play_moves;
+
+    #[test]
+    fn repetitions_are_counted_since_the_last_irreversible_move() {
+        let move_generator = MoveGenerator::new();
+        let mut test_board = ChessBoard::new();
+
+        test_board.setup_initial_position();
+
+        play_moves(&move_generator, &mut test_board, &["g1f3", "g8f6", "f3g1", "f6g8"]);
+        assert_eq!(test_board.count_repetitions(), 1);
+
+        play_moves(&move_generator, &mut test_board, &["g1f3", "g8f6", "f3g1", "f6g8"]);
+        assert_eq!(test_board.count_repetitions(), 2);
+
+        play_moves(&move_generator, &mut test_board, &["e2e4"]);
+        assert_eq!(test_board.count_repetitions(), 0);
+
+        test_board.build_board_from_fen_string("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1".to_string());
+
+        // Giving up castling rights changes the position even though the pieces return.
+        play_moves(&move_generator, &mut test_board, &["a1a2", "e8d8", "a2a1", "d8e8"]);
+        assert_eq!(test_board.count_repetitions(), 0);
+    }
+
+    #[test]
+    fn fifty_move_rule_and_insufficient_material() {
+        let mut test_board = ChessBoard::new();
+
+        test_board.build_board_from_fen_string("4k3/8/8/8/8/8/8/R3K3 w - - 99 80".to_string());
+        assert!(!test_board.is_fifty_move_draw());
+
+        test_board.build_board_from_fen_string("4k3/8/8/8/8/8/8/R3K3 w - - 100 80".to_string());
+        assert!(test_board.is_fifty_move_draw());
+
+        let dead_positions = [
+            "4k3/8/8/8/8/8/8/4K3 w - -",
+            "4k3/8/8/8/8/8/8/4KB2 w - -",
+            "4k3/8/8/8/8/8/8/4KN2 w - -",
+            "4kb2/8/8/8/8/8/8/2B1K3 w - -",
+        ];
+
+        let live_positions = [
+            "4k3/8/8/8/8/8/7P/4K3 w - -",
+            "4k3/8/8/8/8/8/8/3NKN2 w - -",
+            "4k3/8/8/8/8/8/8/2B1KB2 w - -",
+            "4kb2/8/8/8/8/8/8/3BK3 w - -",
+        ];
+
+        for fen_string in dead_positions.iter() {
+            test_board.build_board_from_fen_string(fen_string.to_string());
+            assert!(test_board.is_insufficient_material(), "{}", fen_string);
+        }
+
+        for fen_string in live_positions.iter() {
+            test_board.build_board_from_fen_string(fen_string.to_string());
+            assert!(!test_board.is_insufficient_material(), "{}", fen_string);
+        }
+    }
+}
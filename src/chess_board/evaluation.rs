@@ -1,99 +1,208 @@
-use chess_board::ChessBoard;
-
-static PAWN_SQUARE_TABLE: [i64; 120] = [
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,  50,  50,  50,  50,  50,  50,  50,  50,   0,
-    0,  10,  10,  20,  30,  30,  20,  10,  10,   0,
-    0,   5,   5,  10,  27,  27,  10,   5,   5,   0,
-    0,   0,   0,   0,  25,  25,   0,   0,   0,   0,
-    0,   5,  -5, -10,   0,   0, -10,  -5,   5,   0,
-    0,   5,  10,  10, -25, -25,  10,  10,   5,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+use std::cmp::min;
+use std::ops::{Add, AddAssign, Neg, Sub};
+use chess_board::{ChessBoard, PawnHashTable, get_castling_rook_squares};
+use chess_move::Move;
+
+static CASTLING_BONUS: i64 = 110;
+
+// Knights and bishops count one, rooks two and queens four, so the starting position is at 24
+// and bare kings and pawns are at 0.
+const MAX_PHASE: i64 = 24;
+
+// Every term is kept once as it matters with all pieces on the board and once as it matters in
+// the endgame, the score in between is blended by the game phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(super) struct TaperedScore {
+    middlegame: i64,
+    endgame: i64,
+}
+
+impl TaperedScore {
+    pub(super) const fn new(middlegame: i64, endgame: i64) -> TaperedScore {
+        TaperedScore { middlegame, endgame }
+    }
+
+    pub(super) fn scale(self, factor: i64) -> TaperedScore {
+        TaperedScore::new(self.middlegame * factor, self.endgame * factor)
+    }
+
+    // Promotions can push the phase past the start, it then still counts as a middlegame.
+    pub(super) fn taper(self, phase: i64) -> i64 {
+        let phase = min(phase, MAX_PHASE);
+
+        (self.middlegame * phase + self.endgame * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl Add for TaperedScore {
+    type Output = TaperedScore;
+
+    fn add(self, other: TaperedScore) -> TaperedScore {
+        TaperedScore::new(self.middlegame + other.middlegame, self.endgame + other.endgame)
+    }
+}
+
+impl Sub for TaperedScore {
+    type Output = TaperedScore;
+
+    fn sub(self, other: TaperedScore) -> TaperedScore {
+        TaperedScore::new(self.middlegame - other.middlegame, self.endgame - other.endgame)
+    }
+}
+
+impl Neg for TaperedScore {
+    type Output = TaperedScore;
+
+    fn neg(self) -> TaperedScore {
+        TaperedScore::new(-self.middlegame, -self.endgame)
+    }
+}
+
+impl AddAssign for TaperedScore {
+    fn add_assign(&mut self, other: TaperedScore) {
+        *self = *self + other;
+    }
+}
+
+static MIDDLEGAME_PAWN_SQUARE_TABLE: [i64; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  27,  27,  10,   5,   5,
+      0,   0,   0,  25,  25,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -25, -25,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
 ];
 
-static KNIGHT_SQUARE_TABLE: [i64; 120] = [
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0, -50, -40, -30, -30, -30, -30, -40, -50,   0,
-    0, -40, -20,   0,   0,   0,   0, -20, -40,   0,
-    0, -30,   0,  10,  15,  15,  10,   0, -30,   0,
-    0, -30,   5,  15,  20,  20,  15,   5, -30,   0,
-    0, -30,   0,  15,  20,  20,  15,   0, -30,   0,
-    0, -30,   5,  10,  15,  15,  10,   5, -30,   0,
-    0, -40, -20,   0,   5,   5,   0, -20, -40,   0,
-    0, -50, -40, -20, -30, -30, -20, -40, -50,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+static MIDDLEGAME_KNIGHT_SQUARE_TABLE: [i64; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -20, -30, -30, -20, -40, -50,
 ];
 
-static BISHOP_SQUARE_TABLE: [i64; 120] = [
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0, -20, -10, -10, -10, -10, -10, -10, -20,   0,
-    0, -10,   0,   0,   0,   0,   0,   0, -10,   0,
-    0, -10,   0,   5,  10,  10,   5,   0, -10,   0,
-    0, -10,   5,   5,  10,  10,   5,   5, -10,   0,
-    0, -10,   0,  10,  10,  10,  10,   0, -10,   0,
-    0, -10,  10,  10,  10,  10,  10,  10, -10,   0,
-    0, -10,   5,   0,   0,   0,   0,   5, -10,   0,
-    0, -20, -10, -40, -10, -10, -40, -10, -20,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+static MIDDLEGAME_BISHOP_SQUARE_TABLE: [i64; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -40, -10, -10, -40, -10, -20,
 ];
 
-static ROOK_SQUARE_TABLE: [i64; 120] = [
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,  0 ,   0,   0,   0,
-    0,   5,  10,  10,  10,  10,  10,  10,   5,   0,
-    0,  -5,   0,   0,   0,   0,   0,   0,  -5,   0,
-    0,  -5,   0,   0,   0,   0,   0,   0,  -5,   0,
-    0,  -5,   0,   0,   0,   0,   0,   0,  -5,   0,
-    0,  -5,   0,   0,   0,   0,   0,   0,  -5,   0,
-    0,  -5,   0,   0,   0,   0,   0,   0,  -5,   0,
-    0,   0,   0,   0,   5,   5,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+static MIDDLEGAME_ROOK_SQUARE_TABLE: [i64; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
 ];
 
-static QUEEN_SQUARE_TABLE: [i64; 120] = [
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0, -20, -10, -10,  -5,  -5, -10, -10, -20,   0,
-    0, -10,   0,   0,   0,   0,   0,   0, -10,   0,
-    0, -10,   0,   5,   5,   5,   5,   0, -10,   0,
-    0,  -5,   0,   5,   5,   5,   5,   0,  -5,   0,
-    0,   0,   0,   5,   5,   5,   5,   0,  -5,   0,
-    0, -10,   5,   5,   5,   5,   5,   0, -10,   0,
-    0, -10,   0,   5,   0,   0,   0,   0, -10,   0,
-    0, -20, -10, -10,  -5,  -5, -10, -10, -20,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+static MIDDLEGAME_QUEEN_SQUARE_TABLE: [i64; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
 ];
 
-static KING_SQUARE_TABLE: [i64; 120] = [
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0, -30, -40, -40, -50, -50, -40, -40, -30,   0,
-    0, -30, -40, -40, -50, -50, -40, -40, -30,   0,
-    0, -30, -40, -40, -50, -50, -40, -40, -30,   0,
-    0, -30, -40, -40, -50, -50, -40, -40, -30,   0,
-    0, -20, -30, -30, -40, -40, -30, -30, -20,   0,
-    0, -10, -20, -20, -20, -20, -20, -20, -10,   0,
-    0,  20,  20,   0,   0,   0,   0,  20,  20,   0,
-    0,  20,  30,  10,   0,   0,  10,  30,  20,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-    0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+static MIDDLEGAME_KING_SQUARE_TABLE: [i64; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+
+static ENDGAME_PAWN_SQUARE_TABLE: [i64; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     80,  80,  80,  80,  80,  80,  80,  80,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     30,  30,  30,  30,  30,  30,  30,  30,
+     15,  15,  15,  15,  15,  15,  15,  15,
+      5,   5,   5,   5,   5,   5,   5,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+static ENDGAME_KNIGHT_SQUARE_TABLE: [i64; 64] = [
+    -40, -30, -20, -20, -20, -20, -30, -40,
+    -30, -15,  -5,   0,   0,  -5, -15, -30,
+    -20,  -5,  10,  15,  15,  10,  -5, -20,
+    -20,   0,  15,  20,  20,  15,   0, -20,
+    -20,   0,  15,  20,  20,  15,   0, -20,
+    -20,  -5,  10,  15,  15,  10,  -5, -20,
+    -30, -15,  -5,   0,   0,  -5, -15, -30,
+    -40, -30, -20, -20, -20, -20, -30, -40,
+];
+
+static ENDGAME_BISHOP_SQUARE_TABLE: [i64; 64] = [
+    -15, -10, -10, -10, -10, -10, -10, -15,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -15, -10, -10, -10, -10, -10, -10, -15,
+];
+
+static ENDGAME_ROOK_SQUARE_TABLE: [i64; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     10,  10,  10,  10,  10,  10,  10,  10,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+static ENDGAME_QUEEN_SQUARE_TABLE: [i64; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+    -10,   5,  10,  10,  10,  10,   5, -10,
+     -5,   5,  10,  15,  15,  10,   5,  -5,
+     -5,   5,  10,  15,  15,  10,   5,  -5,
+    -10,   5,  10,  10,  10,  10,   5, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+// With the queens gone the king has to walk to the centre and take part.
+static ENDGAME_KING_SQUARE_TABLE: [i64; 64] = [
+    -50, -30, -30, -30, -30, -30, -30, -50,
+    -30, -10,   0,   0,   0,   0, -10, -30,
+    -30,   0,  20,  30,  30,  20,   0, -30,
+    -30,   0,  30,  40,  40,  30,   0, -30,
+    -30,   0,  30,  40,  40,  30,   0, -30,
+    -30,   0,  20,  30,  30,  20,   0, -30,
+    -30, -20,   0,   0,   0,   0, -20, -30,
+    -50, -40, -30, -20, -20, -30, -40, -50,
 ];
 
 impl ChessBoard {
-    pub(super) fn evaluate_move(&mut self, location: usize, destination: usize) {
-        let location_piece = self.board_vector[location];
-        let destination_piece = self.board_vector[destination];
+    pub(super) fn evaluate_move(&mut self, chess_move: Move) {
+        let location = chess_move.get_from();
+        let destination = chess_move.get_to();
+        let location_piece = self.squares[location];
 
         let location_score = self.match_square_to_score(location, location_piece);
 
@@ -101,62 +210,167 @@ impl ChessBoard {
 
         let mut score = destination_score - location_score;
 
-        if destination_piece.is_lowercase() {
-            let capture_bonus = self.evaluate_piece_score(destination_piece);
+        if chess_move.is_capture() {
+            let capture_square = if chess_move.is_en_passant() {
+                self.get_en_passant_capture_square(destination)
+            } else {
+                destination
+            };
 
-            score += capture_bonus + self.match_square_to_score(destination, destination_piece);
+            let capture_bonus = self.evaluate_piece_score(chess_move.captured_piece);
+
+            score += capture_bonus + self.match_square_to_score(capture_square, chess_move.captured_piece);
+            self.phase -= get_piece_phase(chess_move.captured_piece);
         }
 
-        self.score += score;
-    }
+        // The pawn disappears from the promotion square and the new piece takes its place.
+        if chess_move.is_promotion() {
+            let promotion_bonus = self.evaluate_piece_score(chess_move.promotion) - self.evaluate_piece_score(location_piece);
 
-    pub(super) fn evaluate_en_passant_move(&mut self, location: usize, destination: usize) {
-        self.evaluate_move(location, destination);
+            score += promotion_bonus + self.match_square_to_score(destination, chess_move.promotion) - destination_score;
+            self.phase += get_piece_phase(chess_move.promotion);
+        }
 
-        let en_passant_square_piece = self.board_vector[destination + 10];
+        if chess_move.is_castling() {
+            let (rook_location, rook_destination) = get_castling_rook_squares(destination);
+            let rook = self.squares[rook_location];
 
-        let capture_bonus = self.evaluate_piece_score(en_passant_square_piece);
+            score += self.match_square_to_score(rook_destination, rook) - self.match_square_to_score(rook_location, rook);
+            score += TaperedScore::new(CASTLING_BONUS, 0);
+        }
 
-        self.score += capture_bonus + self.match_square_to_score(destination + 10, en_passant_square_piece);
+        self.score += score;
     }
 
-    pub(super) fn evaluate_pawn_promotion(&mut self, location: usize, promoted_piece: char) {
-        let promotion_bonus = self.evaluate_piece_score(promoted_piece);
+    // Material and piece placement of the whole board from the side to move's point of view, for
+    // positions that weren't reached move by move.
+    pub(super) fn compute_score(&self) -> TaperedScore {
+        let mut score = TaperedScore::default();
+
+        for (square, &piece) in self.squares.iter().enumerate() {
+            if piece == '.' {
+                continue;
+            }
 
-        self.score += promotion_bonus + self.match_square_to_score(location, promoted_piece);;
+            let piece_score = self.evaluate_piece_score(piece) + self.match_square_to_score(square, piece);
+
+            if piece.is_uppercase() == self.current_color {
+                score += piece_score;
+            } else {
+                score += -piece_score;
+            }
+        }
+
+        score
     }
 
-    pub fn add_castling_bonus(&mut self) {
-        self.score += 110;
+    pub(super) fn compute_phase(&self) -> i64 {
+        self.squares.iter().map(|&piece| get_piece_phase(piece)).sum()
     }
 
-    fn match_square_to_score(&self, index: usize, piece: char) -> i64 {
-        let destination_score = match piece.to_ascii_uppercase() {
-            'P' => PAWN_SQUARE_TABLE[index],
-            'N' => KNIGHT_SQUARE_TABLE[index],
-            'B' => BISHOP_SQUARE_TABLE[index],
-            'R' => ROOK_SQUARE_TABLE[index],
-            'Q' => QUEEN_SQUARE_TABLE[index],
-            'K' => KING_SQUARE_TABLE[index],
-            _   => 0
+    // Tables are written from white's point of view with rank 8 on top, black pieces look them up
+    // rotated.
+    fn match_square_to_score(&self, square: usize, piece: char) -> TaperedScore {
+        let index = if piece.is_uppercase() {
+            square ^ 56
+        } else {
+            square ^ 7
+        };
+
+        let (middlegame_table, endgame_table) = match piece.to_ascii_uppercase() {
+            'P' => (&MIDDLEGAME_PAWN_SQUARE_TABLE, &ENDGAME_PAWN_SQUARE_TABLE),
+            'N' => (&MIDDLEGAME_KNIGHT_SQUARE_TABLE, &ENDGAME_KNIGHT_SQUARE_TABLE),
+            'B' => (&MIDDLEGAME_BISHOP_SQUARE_TABLE, &ENDGAME_BISHOP_SQUARE_TABLE),
+            'R' => (&MIDDLEGAME_ROOK_SQUARE_TABLE, &ENDGAME_ROOK_SQUARE_TABLE),
+            'Q' => (&MIDDLEGAME_QUEEN_SQUARE_TABLE, &ENDGAME_QUEEN_SQUARE_TABLE),
+            'K' => (&MIDDLEGAME_KING_SQUARE_TABLE, &ENDGAME_KING_SQUARE_TABLE),
+            _   => return TaperedScore::default()
         };
 
-        destination_score
+        TaperedScore::new(middlegame_table[index], endgame_table[index])
     }
 
-    fn evaluate_piece_score(&self, piece: char) -> i64 {
-        match piece.to_ascii_uppercase() {
-            'P' => 100,
-            'N' => 310,
-            'B' => 370,
-            'R' => 500,
-            'Q' => 950,
-            'K' => 60000,
-            _   => 0
+    fn evaluate_piece_score(&self, piece: char) -> TaperedScore {
+        let piece_value = get_piece_value(piece);
+
+        TaperedScore::new(piece_value, piece_value)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_score(&self) -> i64 {
+        self.score.taper(self.phase)
+    }
+
+    /// The static evaluation in centipawns from the side to move's point of view. Pawn structure
+    /// is looked up in and stored to the given table.
+    pub fn evaluate(&self, pawn_hash_table: &mut PawnHashTable) -> i64 {
+        let mut positional_score = self.evaluate_pawn_structure(pawn_hash_table) + self.evaluate_piece_activity();
+        let mut king_safety = self.evaluate_king_safety();
+
+        if !self.current_color {
+            positional_score = -positional_score;
+            king_safety = -king_safety;
         }
+
+        (self.score + positional_score).taper(self.phase) + king_safety
+    }
+}
+
+fn get_piece_phase(piece: char) -> i64 {
+    match piece.to_ascii_uppercase() {
+        'N' | 'B' => 1,
+        'R'       => 2,
+        'Q'       => 4,
+        _         => 0
+    }
+}
+
+pub fn get_piece_value(piece: char) -> i64 {
+    match piece.to_ascii_uppercase() {
+        'P' => 100,
+        'N' => 310,
+        'B' => 370,
+        'R' => 500,
+        'Q' => 950,
+        'K' => 60000,
+        _   => 0
     }
+}
+
+#[cfg(test)]
+mod evaluation_tests {
+    use chess_board::ChessBoard;
+    use test_util::for_each_position;
+
+    // The castling bonus only exists for boards that castled move by move, so castling lines are
+    // left out of the comparison.
+    #[test]
+    fn incremental_evaluation_matches_recomputation_in_perft_positions() {
+        for_each_position(3, |current_board| {
+            if current_board.undo_stack.iter().any(|undo_state| undo_state.chess_move.is_castling()) {
+                return;
+            }
+
+            assert_eq!(current_board.score, current_board.compute_score());
+            assert_eq!(current_board.phase, current_board.compute_phase());
+        });
+    }
+
+    #[test]
+    fn kings_hide_in_the_middlegame_and_centralise_in_the_endgame() {
+        let mut cornered_king = ChessBoard::new();
+        let mut central_king = ChessBoard::new();
+
+        cornered_king.build_board_from_fen_string("r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/2N2N2/PPPP1PPP/R1BQ1RK1 w - - 0 1".to_string());
+        central_king.build_board_from_fen_string("r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/2N1KN2/PPPP1PPP/R1BQ1R2 w - - 0 1".to_string());
+
+        assert_eq!(cornered_king.phase, 24);
+        assert!(cornered_king.get_score() > central_king.get_score());
+
+        cornered_king.build_board_from_fen_string("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1".to_string());
+        central_king.build_board_from_fen_string("6k1/5ppp/8/8/4K3/8/5PPP/8 w - - 0 1".to_string());
 
-    pub fn get_score(&self) -> i64 {
-        self.score
+        assert_eq!(cornered_king.phase, 0);
+        assert!(cornered_king.get_score() < central_king.get_score());
     }
 }
@@ -0,0 +1,348 @@
+use std::fmt;
+use chess_board::{ChessBoard, KING, PAWN, get_square_index_from_chess_notation, get_square_in_chess_notation};
+use bitboard::{RANK_1, RANK_8, square_bitboard};
+
+/// Why a FEN string could not be read.
+#[derive(Clone, PartialEq, Debug)]
+pub enum FenError {
+    /// The named field is missing.
+    MissingField(&'static str),
+    /// The piece placement has the given number of ranks instead of 8.
+    WrongRankCount(usize),
+    /// The rank, counted from 1, covers more than 8 squares.
+    RankTooLong(usize),
+    /// The rank, counted from 1, covers fewer than 8 squares.
+    RankTooShort(usize),
+    /// The character doesn't stand for any piece.
+    BadPieceLetter(char),
+    /// The king of the given letter is missing.
+    MissingKing(char),
+    /// The king of the given letter appears more than once.
+    TooManyKings(char),
+    /// A pawn stands on the first or eighth rank.
+    PawnsOnBackRank,
+    /// The active colour field is neither "w" nor "b".
+    BadActiveColor(String),
+    /// The castling field is malformed or grants rights the position can't have.
+    BadCastlingField(String),
+    /// The en passant field can't follow a double pawn push in the position.
+    ImpossibleEnPassantSquare(String),
+    /// A move counter isn't a number.
+    BadMoveCounter(String),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::MissingField(field)               => write!(f, "missing {} field", field),
+            FenError::WrongRankCount(rank_count)        => write!(f, "expected 8 ranks but found {}", rank_count),
+            FenError::RankTooLong(rank)                 => write!(f, "rank {} has more than 8 squares", rank),
+            FenError::RankTooShort(rank)                => write!(f, "rank {} has fewer than 8 squares", rank),
+            FenError::BadPieceLetter(letter)            => write!(f, "'{}' is not a piece letter", letter),
+            FenError::MissingKing(king)                 => write!(f, "no '{}' on the board", king),
+            FenError::TooManyKings(king)                => write!(f, "more than one '{}' on the board", king),
+            FenError::PawnsOnBackRank                   => write!(f, "pawns on the first or eighth rank"),
+            FenError::BadActiveColor(field)             => write!(f, "'{}' is not an active colour", field),
+            FenError::BadCastlingField(field)           => write!(f, "castling field '{}' doesn't fit the position", field),
+            FenError::ImpossibleEnPassantSquare(field)  => write!(f, "'{}' can't be an en passant square", field),
+            FenError::BadMoveCounter(field)             => write!(f, "'{}' is not a move counter", field),
+        }
+    }
+}
+
+impl ChessBoard {
+    /// Reads a position from FEN. The two move counters may be left out.
+    pub fn from_fen(fen_string: &str) -> Result<ChessBoard, FenError> {
+        let mut new_board = ChessBoard::empty();
+        let mut fen_fields = fen_string.split_whitespace();
+
+        let board_field = fen_fields.next().ok_or(FenError::MissingField("piece placement"))?;
+        let current_color_field = fen_fields.next().ok_or(FenError::MissingField("active colour"))?;
+        let castling_field = fen_fields.next().ok_or(FenError::MissingField("castling"))?;
+        let en_passant_field = fen_fields.next().ok_or(FenError::MissingField("en passant"))?;
+
+        new_board.set_pieces_from_fen_field(board_field)?;
+        new_board.set_current_color_from_fen_field(current_color_field)?;
+        new_board.set_castling_from_fen_field(castling_field)?;
+        new_board.set_en_passant_square_from_fen_field(en_passant_field)?;
+
+        // Both counters are often left out, a fresh count is the best guess then.
+        new_board.halfmove_clock = parse_move_counter(fen_fields.next(), 0)?;
+        new_board.fullmove_number = parse_move_counter(fen_fields.next(), 1)?;
+
+        new_board.hash_key = new_board.compute_hash_key();
+        new_board.pawn_hash_key = new_board.compute_pawn_hash_key();
+        new_board.score = new_board.compute_score();
+        new_board.phase = new_board.compute_phase();
+
+        Ok(new_board)
+    }
+
+    pub(crate) fn build_board_from_fen_string(&mut self, fen_string: String) {
+        match ChessBoard::from_fen(&fen_string) {
+            Ok(new_board) => *self = new_board,
+            Err(fen_error) => panic!("Error: Invalid fen string, {}!!!", fen_error),
+        }
+    }
+
+    fn set_pieces_from_fen_field(&mut self, board_field: &str) -> Result<(), FenError> {
+        let board_string: Vec<&str> = board_field.split('/').collect();
+
+        if board_string.len() != 8 {
+            return Err(FenError::WrongRankCount(board_string.len()));
+        }
+
+        for (row, split) in board_string.iter().enumerate() {
+            self.build_board_segment(7 - row, split)?;
+        }
+
+        for &color in [true, false].iter() {
+            let king = if color { 'K' } else { 'k' };
+
+            match self.get_pieces(KING, color).count_ones() {
+                0 => return Err(FenError::MissingKing(king)),
+                1 => {},
+                _ => return Err(FenError::TooManyKings(king)),
+            }
+        }
+
+        if self.piece_bitboards[PAWN] & (RANK_1 | RANK_8) != 0 {
+            return Err(FenError::PawnsOnBackRank);
+        }
+
+        Ok(())
+    }
+
+    fn build_board_segment(&mut self, rank: usize, partial_fen_string: &str) -> Result<(), FenError> {
+        let mut file = 0;
+
+        for square in partial_fen_string.chars() {
+            let square_count = match square {
+                '1'..='8' => square.to_digit(10).unwrap() as usize,
+                'P' | 'N' | 'B' | 'R' | 'Q' | 'K' | 'p' | 'n' | 'b' | 'r' | 'q' | 'k' => 1,
+                _ => return Err(FenError::BadPieceLetter(square)),
+            };
+
+            if file + square_count > 8 {
+                return Err(FenError::RankTooLong(rank + 1));
+            }
+
+            if square.is_alphabetic() {
+                self.put_piece(rank * 8 + file, square);
+            }
+
+            file += square_count;
+        }
+
+        if file < 8 {
+            return Err(FenError::RankTooShort(rank + 1));
+        }
+
+        Ok(())
+    }
+
+    fn set_current_color_from_fen_field(&mut self, current_color_fen_field: &str) -> Result<(), FenError> {
+        match current_color_fen_field {
+            "w" => self.current_color = true,
+            "b" => self.current_color = false,
+            _   => return Err(FenError::BadActiveColor(current_color_fen_field.to_string())),
+        }
+
+        Ok(())
+    }
+
+    // Every right needs its king and rook still standing on their original squares.
+    fn set_castling_from_fen_field(&mut self, castling_fen_field: &str) -> Result<(), FenError> {
+        self.white_castling_rights = (false, false);
+        self.black_castling_rights = (false, false);
+
+        if castling_fen_field == "-" {
+            return Ok(());
+        }
+
+        let bad_castling_field = FenError::BadCastlingField(castling_fen_field.to_string());
+
+        for castling_right in castling_fen_field.chars() {
+            let (king_square, rook_square, castling_rights) = match castling_right {
+                'K' => (4, 7, &mut self.white_castling_rights.1),
+                'Q' => (4, 0, &mut self.white_castling_rights.0),
+                'k' => (60, 63, &mut self.black_castling_rights.1),
+                'q' => (60, 56, &mut self.black_castling_rights.0),
+                _   => return Err(bad_castling_field),
+            };
+
+            if *castling_rights {
+                return Err(bad_castling_field);
+            }
+
+            *castling_rights = true;
+
+            let (king, rook) = if castling_right.is_uppercase() { ('K', 'R') } else { ('k', 'r') };
+
+            if self.squares[king_square] != king || self.squares[rook_square] != rook {
+                return Err(bad_castling_field);
+            }
+        }
+
+        Ok(())
+    }
+
+    // The pawn that just moved two squares must stand in front of the square it skipped, with the
+    // square itself and the one it came from left empty.
+    fn set_en_passant_square_from_fen_field(&mut self, en_passant_fen_field: &str) -> Result<(), FenError> {
+        if en_passant_fen_field == "-" {
+            self.en_passant_square = None;
+            return Ok(());
+        }
+
+        let impossible_en_passant_square = FenError::ImpossibleEnPassantSquare(en_passant_fen_field.to_string());
+
+        let en_passant_square = get_square_index_from_chess_notation(en_passant_fen_field)
+            .ok_or(impossible_en_passant_square.clone())?;
+
+        let (en_passant_rank, pushed_pawn_square, starting_square, pushed_pawn) = if self.current_color {
+            (5, en_passant_square.wrapping_sub(8), en_passant_square + 8, 'p')
+        } else {
+            (2, en_passant_square + 8, en_passant_square.wrapping_sub(8), 'P')
+        };
+
+        if en_passant_square / 8 != en_passant_rank
+            || self.squares[pushed_pawn_square] != pushed_pawn
+            || self.get_occupancy() & (square_bitboard(en_passant_square) | square_bitboard(starting_square)) != 0 {
+            return Err(impossible_en_passant_square);
+        }
+
+        self.en_passant_square = Some(en_passant_square);
+
+        Ok(())
+    }
+
+    /// Writes the position as FEN.
+    pub fn to_fen(&self) -> String {
+        let mut board_string = String::new();
+
+        for rank in (0..8).rev() {
+            let mut empty_squares = 0;
+
+            for file in 0..8 {
+                let piece = self.squares[rank * 8 + file];
+
+                if piece == '.' {
+                    empty_squares += 1;
+                    continue;
+                }
+
+                if empty_squares > 0 {
+                    board_string.push_str(&empty_squares.to_string());
+                    empty_squares = 0;
+                }
+
+                board_string.push(piece);
+            }
+
+            if empty_squares > 0 {
+                board_string.push_str(&empty_squares.to_string());
+            }
+
+            if rank > 0 {
+                board_string.push('/');
+            }
+        }
+
+        let current_color = if self.current_color { "w" } else { "b" };
+
+        let mut castling = String::new();
+
+        for &(castling_right, letter) in [(self.white_castling_rights.1, 'K'), (self.white_castling_rights.0, 'Q'),
+                                          (self.black_castling_rights.1, 'k'), (self.black_castling_rights.0, 'q')].iter() {
+            if castling_right {
+                castling.push(letter);
+            }
+        }
+
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant_square = match self.en_passant_square {
+            Some(en_passant_square) => get_square_in_chess_notation(en_passant_square),
+            None => "-".to_string(),
+        };
+
+        format!("{} {} {} {} {} {}", board_string, current_color, castling, en_passant_square,
+                self.halfmove_clock, self.fullmove_number)
+    }
+}
+
+fn parse_move_counter(move_counter_field: Option<&str>, default_value: usize) -> Result<usize, FenError> {
+    match move_counter_field {
+        Some(field) => field.parse().map_err(|_| FenError::BadMoveCounter(field.to_string())),
+        None => Ok(default_value),
+    }
+}
+
+#[cfg(test)]
+mod fen_tests {
+    use chess_board::{ChessBoard, FenError};
+    use move_generator::MoveGenerator;
+    use test_util::{for_each_position, play_moves};
+
+    #[test]
+    fn fen_round_trip_in_perft_positions() {
+        for_each_position(3, |current_board| {
+            let rebuilt_board = ChessBoard::from_fen(&current_board.to_fen()).unwrap();
+
+            assert!(rebuilt_board == *current_board, "{}", current_board.to_fen());
+        });
+    }
+
+    #[test]
+    fn new_boards_hold_the_initial_position() {
+        assert_eq!(ChessBoard::new().to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(ChessBoard::default() == ChessBoard::new());
+    }
+
+    #[test]
+    fn invalid_fen_strings_are_rejected() {
+        let fen_errors = [
+            ("", FenError::MissingField("piece placement")),
+            ("4k3/8/8/8/8/8/8/4K3 w", FenError::MissingField("castling")),
+            ("4k3/8/8/8/8/8/4K3 w - -", FenError::WrongRankCount(7)),
+            ("4k3/8/8/8/8/8/8/4K4 w - -", FenError::RankTooLong(1)),
+            ("4k3/8/8/8/8/8/8/4K2 w - -", FenError::RankTooShort(1)),
+            ("4k3/8/8/8/8/8/8/4X3 w - -", FenError::BadPieceLetter('X')),
+            ("8/8/8/8/8/8/8/4K3 w - -", FenError::MissingKing('k')),
+            ("4k3/8/8/8/8/8/8/3KK3 w - -", FenError::TooManyKings('K')),
+            ("4k2P/8/8/8/8/8/8/4K3 w - -", FenError::PawnsOnBackRank),
+            ("4k3/8/8/8/8/8/8/4K3 x - -", FenError::BadActiveColor("x".to_string())),
+            ("4k3/8/8/8/8/8/8/4K3 w K -", FenError::BadCastlingField("K".to_string())),
+            ("r3k3/8/8/8/8/8/8/4K3 w qq -", FenError::BadCastlingField("qq".to_string())),
+            ("4k3/8/8/8/4P3/8/8/4K3 w - e3", FenError::ImpossibleEnPassantSquare("e3".to_string())),
+            ("4k3/8/8/8/8/8/8/4K3 b - e3", FenError::ImpossibleEnPassantSquare("e3".to_string())),
+            ("4k3/8/8/8/8/8/8/4K3 w - z9", FenError::ImpossibleEnPassantSquare("z9".to_string())),
+            ("4k3/8/8/8/8/8/8/4K3 w - - x 1", FenError::BadMoveCounter("x".to_string())),
+        ];
+
+        for (fen_string, fen_error) in fen_errors.iter() {
+            assert_eq!(ChessBoard::from_fen(fen_string).err().as_ref(), Some(fen_error), "{}", fen_string);
+        }
+
+        assert!(ChessBoard::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").is_ok());
+    }
+
+    #[test]
+    fn move_counters_follow_the_game() {
+        let move_generator = MoveGenerator::new();
+        let mut test_board = ChessBoard::new();
+
+        test_board.setup_initial_position();
+
+        play_moves(&move_generator, &mut test_board, &["g1f3", "g8f6", "b1c3", "e7e5"]);
+
+        assert_eq!(test_board.to_fen(), "rnbqkb1r/pppp1ppp/5n2/4p3/8/2N2N2/PPPPPPPP/R1BQKB1R w KQkq e6 0 3");
+
+        test_board.unmake_move();
+
+        assert_eq!(test_board.to_fen(), "rnbqkb1r/pppppppp/5n2/8/8/2N2N2/PPPPPPPP/R1BQKB1R b KQkq - 3 2");
+    }
+}
@@ -0,0 +1,158 @@
+use std::cmp::min;
+use bitboard::{Bitboard, RANK_1, get_squares, square_bitboard, get_attack_tables, get_file_bitboard,
+               get_adjacent_files_bitboard, get_forward_ranks_bitboard};
+use chess_board::{ChessBoard, get_piece_type, PAWN, KNIGHT, BISHOP, ROOK, QUEEN, KING};
+use move_generator::MoveGenerator;
+
+// What one attack on a square next to the king is worth, by the type of the attacking piece.
+// Pawns are covered by the pawn storm instead.
+static KING_ZONE_ATTACK_WEIGHTS: [i64; 6] = [0, 20, 20, 40, 80, 0];
+
+// A lone attacker rarely achieves anything, so only part of the attack weight counts until
+// several pieces join in.
+static ATTACKER_COUNT_SCALE: [i64; 8] = [0, 0, 50, 75, 88, 94, 97, 100];
+
+// Indexed by how many ranks in front of the king the nearest shield pawn on a file stands,
+// 0 standing for no pawn at all.
+static PAWN_SHIELD_PENALTY: [i64; 8] = [-30, 0, -10, -20, -25, -30, -30, -30];
+static OPEN_FILE_PENALTY: i64 = -15;
+
+// Indexed by how many ranks in front of the king the nearest enemy pawn on a file stands.
+static PAWN_STORM_PENALTY: [i64; 8] = [0, 0, -25, -15, -5, 0, 0, 0];
+
+// The material the attacking side needs to be at full strength, counted like the game phase.
+const MAX_ATTACKING_MATERIAL: i64 = 12;
+
+impl ChessBoard {
+    // Scored from white's point of view.
+    pub(super) fn evaluate_king_safety(&self) -> i64 {
+        let move_generator = MoveGenerator::new();
+
+        self.evaluate_king(&move_generator, true) - self.evaluate_king(&move_generator, false)
+    }
+
+    // Everything that endangers the king of the given colour, which matters less the fewer pieces
+    // the opponent has left to attack with.
+    fn evaluate_king(&self, move_generator: &MoveGenerator, color: bool) -> i64 {
+        let king_square = self.find_king_index(color);
+
+        let attacking_material = min(self.get_attacking_material(!color), MAX_ATTACKING_MATERIAL);
+
+        if attacking_material == 0 {
+            return 0;
+        }
+
+        let score = self.evaluate_king_zone_attacks(move_generator, color, king_square)
+            + self.evaluate_pawn_shield(color, king_square);
+
+        score * attacking_material / MAX_ATTACKING_MATERIAL
+    }
+
+    fn evaluate_king_zone_attacks(&self, move_generator: &MoveGenerator, color: bool, king_square: usize) -> i64 {
+        let king_zone = get_attack_tables().get_king_attacks(king_square) | square_bitboard(king_square);
+        let piece_attackers = self.get_color_occupancy(!color)
+            & !self.get_pieces(PAWN, !color)
+            & !self.get_pieces(KING, !color);
+
+        let mut attackers: Bitboard = 0;
+        let mut attack_weight = 0;
+
+        for square in get_squares(king_zone) {
+            let square_attackers = move_generator.get_attackers(self, square, !color, self.get_occupancy())
+                & piece_attackers;
+
+            for attacker in get_squares(square_attackers) {
+                attack_weight += KING_ZONE_ATTACK_WEIGHTS[get_piece_type(self.squares[attacker])];
+            }
+
+            attackers |= square_attackers;
+        }
+
+        let attacker_count = min(attackers.count_ones() as usize, ATTACKER_COUNT_SCALE.len() - 1);
+
+        -attack_weight * ATTACKER_COUNT_SCALE[attacker_count] / 100
+    }
+
+    // Looks at the king's file and both neighbours for own pawns standing guard and enemy pawns
+    // coming closer.
+    fn evaluate_pawn_shield(&self, color: bool, king_square: usize) -> i64 {
+        let own_pawns = self.get_pieces(PAWN, color);
+        let enemy_pawns = self.get_pieces(PAWN, !color);
+        let in_front_of_king = get_forward_ranks_bitboard(color, king_square);
+
+        let king_files = get_file_bitboard(king_square) | get_adjacent_files_bitboard(king_square);
+        let mut score = 0;
+
+        for file_square in get_squares(king_files & RANK_1) {
+            let file = get_file_bitboard(file_square);
+
+            if (own_pawns | enemy_pawns) & file == 0 {
+                score += OPEN_FILE_PENALTY;
+            }
+
+            score += PAWN_SHIELD_PENALTY[get_pawn_distance(color, king_square, own_pawns & file & in_front_of_king)];
+            score += PAWN_STORM_PENALTY[get_pawn_distance(color, king_square, enemy_pawns & file & in_front_of_king)];
+        }
+
+        score
+    }
+
+    fn get_attacking_material(&self, color: bool) -> i64 {
+        let minor_pieces = self.get_pieces(KNIGHT, color) | self.get_pieces(BISHOP, color);
+
+        minor_pieces.count_ones() as i64
+            + 2 * self.get_pieces(ROOK, color).count_ones() as i64
+            + 4 * self.get_pieces(QUEEN, color).count_ones() as i64
+    }
+}
+
+// Ranks between the king and the pawn closest to it, or 0 if there is no pawn.
+fn get_pawn_distance(color: bool, king_square: usize, pawns: Bitboard) -> usize {
+    if pawns == 0 {
+        return 0;
+    }
+
+    let nearest_pawn = if color {
+        pawns.trailing_zeros() as usize
+    } else {
+        63 - pawns.leading_zeros() as usize
+    };
+
+    (nearest_pawn / 8).abs_diff(king_square / 8)
+}
+
+#[cfg(test)]
+mod king_safety_tests {
+    use chess_board::ChessBoard;
+    use move_generator::MoveGenerator;
+
+    fn evaluate_white_king(fen_string: &str) -> i64 {
+        ChessBoard::from_fen(fen_string).unwrap().evaluate_king(&MoveGenerator::new(), true)
+    }
+
+    #[test]
+    fn pawn_shield_and_storm() {
+        let intact_shield = evaluate_white_king("r3k3/8/8/8/8/8/5PPP/6K1 w - - 0 1");
+        let advanced_shield = evaluate_white_king("r3k3/8/8/8/8/6PP/5P2/6K1 w - - 0 1");
+        let missing_shield = evaluate_white_king("r3k3/8/8/8/8/8/5P1P/6K1 w - - 0 1");
+        let pawn_storm = evaluate_white_king("r3k3/8/8/8/8/6p1/5PPP/6K1 w - - 0 1");
+
+        assert_eq!(intact_shield, 0);
+        assert!(advanced_shield < intact_shield);
+        assert!(missing_shield < advanced_shield);
+        assert!(pawn_storm < intact_shield);
+    }
+
+    #[test]
+    fn attacks_on_the_king_zone_add_up() {
+        let one_attacker = evaluate_white_king("4k3/8/8/8/8/7q/5PPP/6K1 w - - 0 1");
+        let two_attackers = evaluate_white_king("4k3/8/8/8/8/5n1q/5PPP/6K1 w - - 0 1");
+
+        assert_eq!(one_attacker, 0);
+        assert!(two_attackers < 0);
+
+        // Without pieces left to attack with, the king is safe wherever it stands.
+        assert_eq!(evaluate_white_king("4k3/8/8/8/8/8/8/6K1 w - - 0 1"), 0);
+        assert_eq!(evaluate_white_king("4k3/8/8/8/8/7p/5P1P/6K1 w - - 0 1"), 0);
+    }
+}
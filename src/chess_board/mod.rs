@@ -1,180 +1,198 @@
+mod draw;
 mod evaluation;
+mod fen;
+mod king_safety;
+mod pawn_structure;
+mod piece_activity;
+mod see;
+mod zobrist;
+
+pub use self::evaluation::get_piece_value;
+use self::evaluation::TaperedScore;
+pub use self::fen::FenError;
+pub use self::pawn_structure::PawnHashTable;
+
+use bitboard::{Bitboard, square_bitboard};
+use chess_move::Move;
+use types::{Color, Piece, Square};
+
+pub const PAWN: usize = 0;
+pub const KNIGHT: usize = 1;
+pub const BISHOP: usize = 2;
+pub const ROOK: usize = 3;
+pub const QUEEN: usize = 4;
+pub const KING: usize = 5;
+
+// Squares count from a1 = 0 along the ranks up to h8 = 63.
+static INITIAL_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+static WHITE_KING_SQUARE: usize = 4;
+static BLACK_KING_SQUARE: usize = 60;
+static A1: usize = 0;
+static H1: usize = 7;
+static A8: usize = 56;
+static H8: usize = 63;
+
+#[derive(Clone)]
+struct UndoState {
+    chess_move: Move,
+    white_castling_rights: (bool, bool),
+    black_castling_rights: (bool, bool),
+    en_passant_square: Option<usize>,
+    halfmove_clock: usize,
+    score: TaperedScore,
+    phase: i64,
+    hash_key: u64,
+    pawn_hash_key: u64,
+}
 
+/// A position along with the moves that led to it, which can be taken back again.
+#[derive(Clone)]
 pub struct ChessBoard {
-    pub board_vector: Vec<char>,
+    squares: [char; 64],
+    piece_bitboards: [Bitboard; 6],
+    color_bitboards: [Bitboard; 2],
     current_color: bool,
     white_castling_rights: (bool, bool),
     black_castling_rights: (bool, bool),
-    pub en_passant_square: usize,
-    en_passant_lifetime: usize,
-    pub last_move: String,
-    score: i64,
+    en_passant_square: Option<usize>,
+    halfmove_clock: usize,
+    fullmove_number: usize,
+    score: TaperedScore,
+    phase: i64,
+    hash_key: u64,
+    pawn_hash_key: u64,
+    undo_stack: Vec<UndoState>,
+}
+
+// Two boards are equal when they hold the same position, no matter how they got there.
+impl PartialEq for ChessBoard {
+    fn eq(&self, other: &ChessBoard) -> bool {
+        self.squares == other.squares
+            && self.piece_bitboards == other.piece_bitboards
+            && self.color_bitboards == other.color_bitboards
+            && self.current_color == other.current_color
+            && self.white_castling_rights == other.white_castling_rights
+            && self.black_castling_rights == other.black_castling_rights
+            && self.en_passant_square == other.en_passant_square
+            && self.halfmove_clock == other.halfmove_clock
+            && self.fullmove_number == other.fullmove_number
+            && self.hash_key == other.hash_key
+    }
+}
+
+impl Default for ChessBoard {
+    fn default() -> ChessBoard {
+        ChessBoard::new()
+    }
 }
 
 impl ChessBoard {
+    /// The position every game starts from, see [`ChessBoard::from_fen`] to set up any other.
     pub fn new() -> ChessBoard {
-        ChessBoard {
-            board_vector: Vec::new(),
-            current_color: true,
-            white_castling_rights: (true, true),
-            black_castling_rights: (true, true),
-            en_passant_square: 0,
-            en_passant_lifetime: 0,
-            last_move: String::new(),
-            score: 0,
-        }
+        let mut new_board = ChessBoard::empty();
+        new_board.setup_initial_position();
+        new_board
     }
 
-    pub fn from(board_vector: Vec<char>) -> ChessBoard {
+    // Without kings the board is no position at all, it only serves to be filled from a FEN.
+    pub(crate) fn empty() -> ChessBoard {
         ChessBoard {
-            board_vector: board_vector.clone(),
+            squares: ['.'; 64],
+            piece_bitboards: [0; 6],
+            color_bitboards: [0; 2],
             current_color: true,
             white_castling_rights: (true, true),
             black_castling_rights: (true, true),
-            en_passant_square: 0,
-            en_passant_lifetime: 0,
-            last_move: String::new(),
-            score: 0,
-        }
-    }
-
-    pub fn clone(&self) -> ChessBoard {
-        ChessBoard {
-            board_vector: self.board_vector.clone(),
-            last_move: self.last_move.clone(),
-            ..*self
+            en_passant_square: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            score: TaperedScore::default(),
+            phase: 0,
+            hash_key: 0,
+            pawn_hash_key: 0,
+            undo_stack: Vec::new(),
         }
     }
 
+    /// Sets up the position every game starts from.
     pub fn setup_initial_position(&mut self) {
-        self.build_board_from_fen_string("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        self.build_board_from_fen_string(INITIAL_POSITION.to_string());
     }
 
-    pub fn setup_position_2(&mut self) {
+    #[cfg(test)]
+    pub(crate) fn setup_position_2(&mut self) {
         self.build_board_from_fen_string("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -".to_string());
     }
 
-    pub fn setup_position_3(&mut self) {
+    #[cfg(test)]
+    pub(crate) fn setup_position_3(&mut self) {
         self.build_board_from_fen_string("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - -".to_string());
     }
 
-    pub fn setup_position_4(&mut self) {
+    #[cfg(test)]
+    pub(crate) fn setup_position_4(&mut self) {
         self.build_board_from_fen_string("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1".to_string());
     }
 
-    pub fn setup_position_5(&mut self) {
+    #[cfg(test)]
+    pub(crate) fn setup_position_5(&mut self) {
         self.build_board_from_fen_string("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8".to_string());
     }
 
-    pub fn setup_position_6(&mut self) {
+    #[cfg(test)]
+    pub(crate) fn setup_position_6(&mut self) {
         self.build_board_from_fen_string("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10".to_string())
     }
 
-    pub fn build_board_from_fen_string(&mut self, fen_string: String) {
-        self.board_vector.clear();
-
-        let filler_segment = vec![' ', ' ', ' ', ' ', ' ', ' ', ' ', ' ', ' ', '\n'];
-
-        self.board_vector.append(&mut filler_segment.clone());
-        self.board_vector.append(&mut filler_segment.clone());
-
-        let split_fen_string: Vec<&str> = fen_string.split(' ').collect();
-
-        let board_string: Vec<&str> = split_fen_string[0].split('/').collect();
-
-        for split in board_string {
-            let mut board_segment = self.build_board_segment(split);
-            self.board_vector.append(&mut board_segment);
-        }
-
-        self.board_vector.append(&mut filler_segment.clone());
-        self.board_vector.append(&mut filler_segment.clone());
-
-        self.set_current_color_from_fen_field(split_fen_string[1]);
-        self.set_castling_from_fen_field(split_fen_string[2]);
-        self.set_en_passant_square_from_fen_field(split_fen_string[3]);
+    pub(crate) fn get_square(&self, target_square: usize) -> char {
+        self.squares[target_square]
     }
 
-    fn build_board_segment(&mut self, partial_fen_string: &str) -> Vec<char> {
-        let mut board_segment: Vec<char> = Vec::new();
-        board_segment.push(' ');
-
-        for square in partial_fen_string.chars() {
-            if square.is_alphabetic() {
-                board_segment.push(square);
-            } else if square.is_alphanumeric() {
-                let square_as_digit = square.to_digit(10).unwrap();
-
-                for _ in 0..square_as_digit {
-                    board_segment.push('.');
-                }
-            }
-        }
-
-        board_segment.push('\n');
-        board_segment
+    /// The piece standing on the square, if any.
+    pub fn get_piece(&self, square: Square) -> Option<Piece> {
+        Piece::from_letter(self.squares[square.get_index()])
     }
 
-    fn set_current_color_from_fen_field(&mut self, current_color_fen_field: &str) {
-        match current_color_fen_field {
-            "w" => self.current_color = true,
-            "b" => {
-                self.current_color = false;
-                self.board_vector = self.return_rotated_board_vector_with_changed_letter_case();
-            },
-            _   => panic!("Error: Can't read active colour information from fen string!!!"),
-        }
+    pub(crate) fn get_pieces(&self, piece_type: usize, color: bool) -> Bitboard {
+        self.piece_bitboards[piece_type] & self.color_bitboards[get_color_index(color)]
     }
 
-    fn set_castling_from_fen_field(&mut self, castling_fen_field: &str) {
-        if castling_fen_field.contains('K') {
-            self.white_castling_rights.1 = true;
-        } else {
-            self.white_castling_rights.1 = false;
-        }
-
-        if castling_fen_field.contains('Q') {
-            self.white_castling_rights.0 = true;
-        } else {
-            self.white_castling_rights.0 = false;
-        }
+    pub(crate) fn get_color_occupancy(&self, color: bool) -> Bitboard {
+        self.color_bitboards[get_color_index(color)]
+    }
 
-        if castling_fen_field.contains('k') {
-            self.black_castling_rights.0 = true;
-        } else {
-            self.black_castling_rights.0 = false;
-        }
+    // Without any pieces besides pawns and the king, passing the turn is often the best move there
+    // is, which is what makes null-move pruning unsafe in such positions.
+    pub(crate) fn has_non_pawn_material(&self, color: bool) -> bool {
+        let non_pawn_pieces = self.piece_bitboards[KNIGHT] | self.piece_bitboards[BISHOP]
+            | self.piece_bitboards[ROOK] | self.piece_bitboards[QUEEN];
 
-        if castling_fen_field.contains('q') {
-            self.black_castling_rights.1 = true;
-        } else {
-            self.black_castling_rights.1 = false;
-        }
+        non_pawn_pieces & self.get_color_occupancy(color) != 0
     }
 
-    fn set_en_passant_square_from_fen_field(&mut self, en_passant_fen_field: &str) {
-        if en_passant_fen_field.contains('-') {
-            return
-        }
-
-        let en_passant_square_index = self.get_piece_index_from_chess_notation(en_passant_fen_field.to_string());
+    pub(crate) fn get_occupancy(&self) -> Bitboard {
+        self.color_bitboards[0] | self.color_bitboards[1]
+    }
 
-        self.set_en_passant_square(en_passant_square_index as i64);
+    fn put_piece(&mut self, square: usize, piece: char) {
+        self.squares[square] = piece;
+        self.piece_bitboards[get_piece_type(piece)] |= square_bitboard(square);
+        self.color_bitboards[get_color_index(piece.is_uppercase())] |= square_bitboard(square);
     }
 
-    pub fn get_square(&self, target_square: i64) -> char {
-        if target_square < 0 && target_square > 119 {
-            panic!("Error: ChessBoard out of bounds!!!");
-        }
+    fn remove_piece(&mut self, square: usize) -> char {
+        let piece = self.squares[square];
 
-        let target_square = target_square as usize;
+        self.squares[square] = '.';
+        self.piece_bitboards[get_piece_type(piece)] &= !square_bitboard(square);
+        self.color_bitboards[get_color_index(piece.is_uppercase())] &= !square_bitboard(square);
 
-        self.board_vector[target_square]
+        piece
     }
 
     fn match_letter_to_unicode_chess_symbol(&self, letter: char, perspective_color: bool) -> char {
-        let matched_letter = if perspective_color {
-            let new_letter = match letter {
+        if perspective_color {
+            match letter {
                 'K' => '♔',
                 'Q' => '♕',
                 'R' => '♖',
@@ -188,11 +206,9 @@ impl ChessBoard {
                 'n' => '♞',
                 'p' => '♟',
                 _   => letter,
-            };
-
-            new_letter
+            }
         } else {
-            let new_letter = match letter {
+            match letter {
                 'K' => '♚',
                 'Q' => '♛',
                 'R' => '♜',
@@ -206,379 +222,396 @@ impl ChessBoard {
                 'n' => '♘',
                 'p' => '♙',
                 _   => letter,
-            };
-
-            new_letter
-        };
-
-        matched_letter
+            }
+        }
     }
 
     fn print_board(&self, white: bool, unicode: bool) {
         let files = if white {
-            "   a b c d e f g h \n "
+            "   a b c d e f g h \n"
         } else {
-            "   h g f e d c b a \n "
+            "   h g f e d c b a \n"
         };
 
-        let ranks = if white {
-            [" ", " ", "8", "7", "6", "5", "4", "3", "2", "1", " ", " "]
-        } else {
-            [" ", " ", "1", "2", "3", "4", "5", "6", "7", "8", " ", " "]
-        };
+        print!("\n{}", files);
 
-        let print_vector = if white == self.current_color {
-            self.board_vector.clone()
-        } else {
-            self.return_rotated_board_vector_with_changed_letter_case()
-        };
+        for row in 0..8 {
+            let rank = if white { 7 - row } else { row };
 
-        for (index, i) in print_vector.iter().enumerate() {
-            if index == 20 || index == 100 {
-                print!("{}", files);
-            }
+            print!(" {} ", rank + 1);
 
-            if index % 10 == 0  {
-                print!("{}", ranks[index / 10]);
-            }
+            for column in 0..8 {
+                let file = if white { column } else { 7 - column };
+                let mut piece = self.squares[rank * 8 + file];
+
+                // Seen from black the pieces swap case, so the side at the bottom stays uppercase.
+                if !white {
+                    piece = if piece.is_uppercase() {
+                        piece.to_ascii_lowercase()
+                    } else {
+                        piece.to_ascii_uppercase()
+                    };
+                }
 
-            if unicode {
-                print!("{} ", self.match_letter_to_unicode_chess_symbol(*i, white));
-            } else {
-                print!("{} ", i);
+                if unicode {
+                    print!("{} ", self.match_letter_to_unicode_chess_symbol(piece, white));
+                } else {
+                    print!("{} ", piece);
+                }
             }
 
+            println!();
         }
+
+        println!("{}", files);
     }
 
+    /// Prints the board with the side to move at the bottom, using chess symbols when `unicode` is set.
     pub fn print_board_from_current_color_perspective(&self, unicode: bool) {
         self.print_board(self.current_color, unicode);
     }
 
+    /// Prints the board with white at the bottom, using chess symbols when `unicode` is set.
     pub fn print_board_from_white_perspective(&self, unicode: bool) {
         self.print_board(true, unicode);
     }
 
+    /// Prints the board with black at the bottom, using chess symbols when `unicode` is set.
     pub fn print_board_from_black_perspective(&self, unicode: bool) {
         self.print_board(false, unicode);
     }
 
-    fn return_rotated_board_vector_with_changed_letter_case(&self) -> Vec<char> {
-        let mut rotated_board: Vec<char> = Vec::new();
-        let mut board_vector_clone = self.board_vector.clone();
-
-        while let Some(square) = board_vector_clone.pop() {
-            if square == '\n' {
-                rotated_board.push(' ');
-            } else if square == ' ' {
-                rotated_board.push('\n');
-            } else {
-                rotated_board.push(square);
-            }
-        }
+    /// Plays a move, which has to be one of the legal moves generated for the position.
+    ///
+    /// The move isn't checked, anything else leaves the board in a broken state.
+    /// [`Game::accept_move`](crate::Game::accept_move) checks a move against the legal ones first.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, when given the null move.
+    pub fn make_move(&mut self, chess_move: Move) {
+        debug_assert!(!chess_move.is_null(), "Error: Can't make the null move!!!");
 
-        let mut rotated_board_piece_changed = rotated_board.clone();
+        let location = chess_move.get_from();
+        let destination = chess_move.get_to();
 
-        for (index, piece) in rotated_board.iter().enumerate() {
-            if piece.is_uppercase() {
-                rotated_board_piece_changed[index] = piece.to_lowercase().to_string().chars().nth(0).unwrap();
-            } else if piece.is_lowercase() {
-                rotated_board_piece_changed[index] = piece.to_uppercase().to_string().chars().nth(0).unwrap();
-            }
-        }
-
-        rotated_board_piece_changed
-    }
-
-    pub fn clone_and_rotate(&self) -> ChessBoard {
-        ChessBoard {
-            board_vector: self.return_rotated_board_vector_with_changed_letter_case(),
-            current_color: !self.current_color,
+        self.undo_stack.push(UndoState {
+            chess_move,
             white_castling_rights: self.white_castling_rights,
             black_castling_rights: self.black_castling_rights,
             en_passant_square: self.en_passant_square,
-            en_passant_lifetime: self.en_passant_lifetime,
-            last_move: self.last_move.clone(),
-            score: -self.score,
-        }
-    }
+            halfmove_clock: self.halfmove_clock,
+            score: self.score,
+            phase: self.phase,
+            hash_key: self.hash_key,
+            pawn_hash_key: self.pawn_hash_key,
+        });
+
+        self.evaluate_move(chess_move);
+        self.hash_move(chess_move);
 
-    fn get_piece_index_from_chess_notation(&self, location: String) -> usize {
-        if location.len() != 2 {
-            panic!("Error: Location in chess notation is wrong!!!");
+        if chess_move.is_en_passant() {
+            let capture_square = self.get_en_passant_capture_square(destination);
+
+            self.remove_piece(capture_square);
+        } else if chess_move.is_capture() {
+            self.remove_piece(destination);
         }
 
-        let mut piece_index = String::new();
+        if chess_move.is_castling() {
+            let (rook_location, rook_destination) = get_castling_rook_squares(destination);
+            let rook = self.remove_piece(rook_location);
 
-        if self.current_color {
-            let second_digit = match location.chars().nth(0).unwrap() {
-                'h' => '8',
-                'g' => '7',
-                'f' => '6',
-                'e' => '5',
-                'd' => '4',
-                'c' => '3',
-                'b' => '2',
-                'a' => '1',
-                _   => panic!("Error: Can't match file to index!!!"),
-            };
-
-            let first_digit = match location.chars().nth(1).unwrap() {
-                '1' => '9',
-                '2' => '8',
-                '3' => '7',
-                '4' => '6',
-                '5' => '5',
-                '6' => '4',
-                '7' => '3',
-                '8' => '2',
-                _   => panic!("Error: Can't match rank to index!!!"),
-            };
-
-            piece_index.push(first_digit);
-            piece_index.push(second_digit);
-
-            piece_index.parse().unwrap()
-        } else {
-            let second_digit = match location.chars().nth(0).unwrap() {
-                'h' => '1',
-                'g' => '2',
-                'f' => '3',
-                'e' => '4',
-                'd' => '5',
-                'c' => '6',
-                'b' => '7',
-                'a' => '8',
-                _   => panic!("Error: Can't match file to index!!!"),
-            };
-
-            let first_digit = match location.chars().nth(1).unwrap() {
-                '1' => '2',
-                '2' => '3',
-                '3' => '4',
-                '4' => '5',
-                '5' => '6',
-                '6' => '7',
-                '7' => '8',
-                '8' => '9',
-                _   => panic!("Error: Can't match rank to index!!!"),
-            };
-
-            piece_index.push(first_digit);
-            piece_index.push(second_digit);
-
-            piece_index.parse().unwrap()
+            self.put_piece(rook_destination, rook);
         }
-    }
 
-    fn get_piece_location_in_chess_notation(&self, location: usize) -> String {
-        let location_as_string = location.to_string();
-        let mut piece_location_notated = String::new();
+        let location_piece = self.remove_piece(location);
 
-        if self.current_color {
-            let rank = match location_as_string.chars().nth(0).unwrap() {
-                '2' => "8",
-                '3' => "7",
-                '4' => "6",
-                '5' => "5",
-                '6' => "4",
-                '7' => "3",
-                '8' => "2",
-                '9' => "1",
-                _   => panic!("Error: Can't match location to a rank!!!"),
-            };
-
-            let file = match location_as_string.chars().nth(1).unwrap() {
-                '1' => "a",
-                '2' => "b",
-                '3' => "c",
-                '4' => "d",
-                '5' => "e",
-                '6' => "f",
-                '7' => "g",
-                '8' => "h",
-                _   => panic!("Error: Can't match location to a file!!!")
-            };
-
-            piece_location_notated.push_str(file);
-            piece_location_notated.push_str(rank);
+        if chess_move.is_promotion() {
+            self.put_piece(destination, chess_move.promotion);
         } else {
-            let rank = match location_as_string.chars().nth(0).unwrap() {
-                '2' => "1",
-                '3' => "2",
-                '4' => "3",
-                '5' => "4",
-                '6' => "5",
-                '7' => "6",
-                '8' => "7",
-                '9' => "8",
-                _   => panic!("Error: Can't match location to a rank!!!"),
-            };
-
-            let file = match location_as_string.chars().nth(1).unwrap() {
-                '1' => "h",
-                '2' => "g",
-                '3' => "f",
-                '4' => "e",
-                '5' => "d",
-                '6' => "c",
-                '7' => "b",
-                '8' => "a",
-                _   => panic!("Error: Can't match location to a file!!!")
-            };
-
-            piece_location_notated.push_str(file);
-            piece_location_notated.push_str(rank);
+            self.put_piece(destination, location_piece);
         }
 
-        piece_location_notated
-    }
+        let new_en_passant_square = if chess_move.is_double_pawn_push() {
+            Some((location + destination) / 2)
+        } else {
+            None
+        };
 
-    pub fn print_last_move(&self) {
-        if !self.last_move.is_empty() {
-            println!("{}", self.last_move);
+        self.set_en_passant_square(new_en_passant_square);
+        self.update_castling_rights_after_move(location, destination);
+
+        if get_piece_type(location_piece) == PAWN || chess_move.is_capture() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
         }
-    }
 
-    pub fn print_last_move_with_moving_side_info(&self) {
-        if !self.last_move.is_empty() {
-            if self.current_color {
-                println!("Black moves: {}", self.last_move);
-            } else {
-                println!("White moves: {}", self.last_move);
-            }
+        if !self.current_color {
+            self.fullmove_number += 1;
         }
+
+        self.current_color = !self.current_color;
+        self.score = -self.score;
+        self.hash_side_to_move();
     }
 
-    pub fn notate_move(&mut self, location: usize, destination: usize) {
-        let mut move_notated = String::new();
+    /// Takes back the last move played.
+    ///
+    /// # Panics
+    ///
+    /// Panics when there is no move to take back.
+    pub fn unmake_move(&mut self) {
+        let undo_state = self.undo_stack.pop().expect("Error: No move to unmake!!!");
+        let chess_move = undo_state.chess_move;
 
-        let location_notated = self.get_piece_location_in_chess_notation(location);
-        let destination_notated = self.get_piece_location_in_chess_notation(destination);
+        let location = chess_move.get_from();
+        let destination = chess_move.get_to();
 
-        move_notated.push_str(&location_notated);
-        move_notated.push_str(&destination_notated);
+        self.current_color = !self.current_color;
 
-        self.last_move = move_notated;
-    }
+        if !self.current_color {
+            self.fullmove_number -= 1;
+        }
 
-    pub fn make_move(&mut self, location: i64, destination: i64) {
-        if location < 0 && location > 119 {
-            panic!("Error: ChessBoard out of bounds!!!");
+        let destination_piece = self.remove_piece(destination);
+
+        if chess_move.is_promotion() {
+            self.put_piece(location, if self.current_color { 'P' } else { 'p' });
+        } else {
+            self.put_piece(location, destination_piece);
         }
 
-        if destination < 0 && destination > 119 {
-            panic!("Error: ChessBoard out of bounds!!!");
+        if chess_move.is_en_passant() {
+            let capture_square = self.get_en_passant_capture_square(destination);
+
+            self.put_piece(capture_square, chess_move.captured_piece);
+        } else if chess_move.is_capture() {
+            self.put_piece(destination, chess_move.captured_piece);
         }
 
-        let location = location as usize;
-        let destination = destination as usize;
+        if chess_move.is_castling() {
+            let (rook_location, rook_destination) = get_castling_rook_squares(destination);
+            let rook = self.remove_piece(rook_destination);
 
-        self.notate_move(location, destination);
-        self.evaluate_move(location, destination);
+            self.put_piece(rook_location, rook);
+        }
 
-        self.board_vector[destination] = self.board_vector[location];
-        self.board_vector[location] = '.';
+        self.white_castling_rights = undo_state.white_castling_rights;
+        self.black_castling_rights = undo_state.black_castling_rights;
+        self.en_passant_square = undo_state.en_passant_square;
+        self.halfmove_clock = undo_state.halfmove_clock;
+        self.score = undo_state.score;
+        self.phase = undo_state.phase;
+        self.hash_key = undo_state.hash_key;
+        self.pawn_hash_key = undo_state.pawn_hash_key;
     }
 
-    pub fn make_en_passant_move(&mut self, location: i64, destination: i64) {
-        if location < 0 && location > 119 {
-            panic!("Error: ChessBoard out of bounds!!!");
-        }
+    // Passes the turn to the other side without moving anything, which only the search has a use
+    // for. The halfmove clock restarts so no repetition is counted across the null move.
+    pub(crate) fn make_null_move(&mut self) {
+        self.undo_stack.push(UndoState {
+            chess_move: Move::null(),
+            white_castling_rights: self.white_castling_rights,
+            black_castling_rights: self.black_castling_rights,
+            en_passant_square: self.en_passant_square,
+            halfmove_clock: self.halfmove_clock,
+            score: self.score,
+            phase: self.phase,
+            hash_key: self.hash_key,
+            pawn_hash_key: self.pawn_hash_key,
+        });
+
+        self.set_en_passant_square(None);
+        self.halfmove_clock = 0;
 
-        if destination < 0 && destination > 119 {
-            panic!("Error: ChessBoard out of bounds!!!");
+        if !self.current_color {
+            self.fullmove_number += 1;
         }
 
-        let location = location as usize;
-        let destination = destination as usize;
+        self.current_color = !self.current_color;
+        self.score = -self.score;
+        self.hash_side_to_move();
+    }
 
-        self.notate_move(location, destination);
-        self.evaluate_en_passant_move(location, destination);
+    pub(crate) fn unmake_null_move(&mut self) {
+        let undo_state = self.undo_stack.pop().expect("Error: No null move to unmake!!!");
 
-        self.board_vector[destination] = self.board_vector[location];
-        self.board_vector[destination + 10] = '.';
-        self.board_vector[location] = '.';
-    }
+        assert!(undo_state.chess_move.is_null(), "Error: Last move was not a null move!!!");
 
-    pub fn promote_pawn(&mut self, location: usize, promoted_piece: char) {
-        if self.last_move.chars().count() == 5 {
-            self.last_move.pop();
-        }
+        self.current_color = !self.current_color;
 
-        self.evaluate_pawn_promotion(location, promoted_piece);
+        if !self.current_color {
+            self.fullmove_number -= 1;
+        }
 
-        self.last_move += &promoted_piece.to_lowercase().to_string();
+        self.en_passant_square = undo_state.en_passant_square;
+        self.halfmove_clock = undo_state.halfmove_clock;
+        self.score = undo_state.score;
+        self.phase = undo_state.phase;
+        self.hash_key = undo_state.hash_key;
+        self.pawn_hash_key = undo_state.pawn_hash_key;
+    }
 
-        self.board_vector[location] = promoted_piece;
+    pub(crate) fn is_after_null_move(&self) -> bool {
+        self.undo_stack.last().is_some_and(|undo_state| undo_state.chess_move.is_null())
     }
 
-    pub fn set_castling_rights(&mut self, a1_castling: bool, h1_castling: bool) {
+    // The pawn captured en passant stands right behind the destination, seen from the mover.
+    fn get_en_passant_capture_square(&self, destination: usize) -> usize {
         if self.current_color {
-            self.white_castling_rights = (a1_castling, h1_castling);
+            destination - 8
         } else {
-            self.black_castling_rights = (a1_castling, h1_castling);
+            destination + 8
+        }
+    }
+
+    fn update_castling_rights_after_move(&mut self, location: usize, destination: usize) {
+        let mut white_castling_rights = self.white_castling_rights;
+        let mut black_castling_rights = self.black_castling_rights;
+
+        for &square in [location, destination].iter() {
+            if square == WHITE_KING_SQUARE {
+                white_castling_rights = (false, false);
+            } else if square == A1 {
+                white_castling_rights.0 = false;
+            } else if square == H1 {
+                white_castling_rights.1 = false;
+            } else if square == BLACK_KING_SQUARE {
+                black_castling_rights = (false, false);
+            } else if square == A8 {
+                black_castling_rights.0 = false;
+            } else if square == H8 {
+                black_castling_rights.1 = false;
+            }
         }
-    }
 
-    pub fn set_left_castling(&mut self, a1_castling: bool) {
-        if self.current_color {
-            self.white_castling_rights = (a1_castling, self.white_castling_rights.1);
-        } else {
-            self.black_castling_rights = (a1_castling, self.black_castling_rights.1);
+        if white_castling_rights != self.white_castling_rights || black_castling_rights != self.black_castling_rights {
+            self.update_castling_rights(white_castling_rights, black_castling_rights);
         }
     }
 
-    pub fn set_right_castling(&mut self, h1_castling: bool) {
-        if self.current_color {
-            self.white_castling_rights = (self.white_castling_rights.0, h1_castling);
+    pub(crate) fn get_castling_rights(&self, color: bool) -> (bool, bool) {
+        if color {
+            self.white_castling_rights
         } else {
-            self.black_castling_rights = (self.black_castling_rights.0, h1_castling);
+            self.black_castling_rights
         }
     }
 
-    pub fn get_left_castling(&self) -> bool {
-        if self.current_color {
-            self.white_castling_rights.0
-        } else {
-            self.black_castling_rights.0
-        }
+    pub(crate) fn get_current_color(&self) -> bool {
+        self.current_color
     }
 
-    pub fn get_right_castling(&self) -> bool {
-        if self.current_color {
-            self.white_castling_rights.1
-        } else {
-            self.black_castling_rights.1
-        }
+    pub(crate) fn get_en_passant_square(&self) -> Option<usize> {
+        self.en_passant_square
     }
 
-    pub fn get_current_color(&self) -> bool {
-        self.current_color
+    /// The side whose turn it is.
+    pub fn get_side_to_move(&self) -> Color {
+        Color::from_bool(self.current_color)
     }
 
-    pub fn set_en_passant_square(&mut self, new_en_passant_square: i64) {
-        if new_en_passant_square < 0 && new_en_passant_square > 119 {
-            panic!("Error: ChessBoard out of bounds!!!");
-        }
+    /// Whether the side may still castle with the rook on the h file, if nothing is in the way.
+    pub fn can_castle_kingside(&self, color: Color) -> bool {
+        self.get_castling_rights(color.is_white()).1
+    }
 
-        let new_en_passant_square = new_en_passant_square as usize;
+    /// Whether the side may still castle with the rook on the a file, if nothing is in the way.
+    pub fn can_castle_queenside(&self, color: Color) -> bool {
+        self.get_castling_rights(color.is_white()).0
+    }
 
+    /// The square a pawn can capture en passant onto, right after a pawn moved two squares.
+    pub fn get_en_passant_target(&self) -> Option<Square> {
+        self.en_passant_square.map(Square::from_index)
+    }
+
+    /// The number of moves by either side since the last capture or pawn move.
+    pub fn get_halfmove_clock(&self) -> usize {
+        self.halfmove_clock
+    }
+
+    /// The number of the current move, which goes up after every black move.
+    pub fn get_fullmove_number(&self) -> usize {
+        self.fullmove_number
+    }
+
+    fn set_en_passant_square(&mut self, new_en_passant_square: Option<usize>) {
+        self.hash_key ^= self.en_passant_hash_key();
         self.en_passant_square = new_en_passant_square;
-        self.en_passant_lifetime = 2;
+        self.hash_key ^= self.en_passant_hash_key();
     }
 
-    pub fn is_en_passant_active(&self) -> bool {
-        if self.en_passant_lifetime > 0 {
-            true
-        } else {
-            false
+    pub(crate) fn find_king_index(&self, color: bool) -> usize {
+        let king = self.get_pieces(KING, color);
+
+        if king == 0 {
+            panic!("Error: King is missing from the board!!!");
         }
+
+        king.trailing_zeros() as usize
     }
 
-    pub fn decrease_en_passant_lifetime(&mut self) {
-        if self.en_passant_lifetime > 0 {
-            self.en_passant_lifetime -= 1;
-        }
+    /// The square the side's king stands on.
+    pub fn get_king_square(&self, color: Color) -> Square {
+        Square::from_index(self.find_king_index(color.is_white()))
     }
 }
+
+pub fn get_piece_type(piece: char) -> usize {
+    match piece.to_ascii_uppercase() {
+        'P' => PAWN,
+        'N' => KNIGHT,
+        'B' => BISHOP,
+        'R' => ROOK,
+        'Q' => QUEEN,
+        'K' => KING,
+        _   => panic!("Error: Can't match piece to a piece type!!!"),
+    }
+}
+
+fn get_color_index(color: bool) -> usize {
+    if color { 0 } else { 1 }
+}
+
+// Castling is encoded as a king move, the rook jumps over from its corner.
+fn get_castling_rook_squares(king_destination: usize) -> (usize, usize) {
+    if king_destination % 8 == 6 {
+        (king_destination + 1, king_destination - 1)
+    } else {
+        (king_destination - 2, king_destination + 1)
+    }
+}
+
+pub fn get_square_index_from_chess_notation(location: &str) -> Option<usize> {
+    let location: Vec<char> = location.chars().collect();
+
+    if location.len() != 2 {
+        return None;
+    }
+
+    let file = match location[0] {
+        'a'..='h' => location[0] as usize - 'a' as usize,
+        _         => return None,
+    };
+
+    let rank = match location[1] {
+        '1'..='8' => location[1] as usize - '1' as usize,
+        _         => return None,
+    };
+
+    Some(rank * 8 + file)
+}
+
+pub fn get_square_in_chess_notation(location: usize) -> String {
+    if location >= 64 {
+        panic!("Error: Can't match location to a square!!!");
+    }
+
+    let file = (b'a' + (location % 8) as u8) as char;
+    let rank = (b'1' + (location / 8) as u8) as char;
+
+    format!("{}{}", file, rank)
+}
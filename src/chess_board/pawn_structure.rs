@@ -0,0 +1,248 @@
+use bitboard::{Bitboard, get_squares, square_bitboard, get_attack_tables, get_file_bitboard,
+               get_adjacent_files_bitboard, get_forward_ranks_bitboard};
+use chess_board::{ChessBoard, PAWN};
+use chess_board::evaluation::TaperedScore;
+
+const PAWN_HASH_TABLE_SIZE: usize = 1 << 14;
+
+static DOUBLED_PAWN_PENALTY: TaperedScore = TaperedScore::new(-10, -20);
+static ISOLATED_PAWN_PENALTY: TaperedScore = TaperedScore::new(-10, -15);
+static BACKWARD_PAWN_PENALTY: TaperedScore = TaperedScore::new(-8, -10);
+static CONNECTED_PAWN_BONUS: TaperedScore = TaperedScore::new(8, 5);
+
+// Both passed pawn tables are indexed by the rank as seen from the pawn's own side.
+static PASSED_PAWN_BONUS: [TaperedScore; 8] = [
+    TaperedScore::new(0, 0),
+    TaperedScore::new(5, 10),
+    TaperedScore::new(10, 15),
+    TaperedScore::new(15, 25),
+    TaperedScore::new(25, 45),
+    TaperedScore::new(40, 70),
+    TaperedScore::new(60, 110),
+    TaperedScore::new(0, 0),
+];
+
+static FREE_PASSED_PAWN_BONUS: [TaperedScore; 8] = [
+    TaperedScore::new(0, 0),
+    TaperedScore::new(0, 5),
+    TaperedScore::new(0, 5),
+    TaperedScore::new(5, 10),
+    TaperedScore::new(10, 20),
+    TaperedScore::new(15, 35),
+    TaperedScore::new(25, 60),
+    TaperedScore::new(0, 0),
+];
+
+#[derive(Clone, Copy)]
+struct PawnEntry {
+    pawn_hash_key: u64,
+    score: TaperedScore,
+    passed_pawns: Bitboard,
+}
+
+/// Keeps the pawn structure scores of positions evaluated before.
+///
+/// Pawn structure only depends on where the pawns stand and changes with few moves, so it is
+/// evaluated once per pawn hash key.
+// Whether a passed pawn's path is free depends on the other pieces too, which is why the passed
+// pawns are kept and that part is added on every lookup.
+pub struct PawnHashTable {
+    entries: Vec<Option<PawnEntry>>,
+}
+
+impl Default for PawnHashTable {
+    fn default() -> PawnHashTable {
+        PawnHashTable::new()
+    }
+}
+
+impl PawnHashTable {
+    /// An empty table.
+    pub fn new() -> PawnHashTable {
+        PawnHashTable {
+            entries: vec![None; PAWN_HASH_TABLE_SIZE],
+        }
+    }
+
+    /// Forgets every score stored so far.
+    pub fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
+            *entry = None;
+        }
+    }
+
+    fn probe(&mut self, current_board: &ChessBoard) -> PawnEntry {
+        let pawn_hash_key = current_board.get_pawn_hash_key();
+        let index = (pawn_hash_key as usize) & (PAWN_HASH_TABLE_SIZE - 1);
+
+        match self.entries[index] {
+            Some(entry) if entry.pawn_hash_key == pawn_hash_key => entry,
+            _ => {
+                let (score, passed_pawns) = current_board.compute_pawn_structure();
+                let entry = PawnEntry {
+                    pawn_hash_key,
+                    score,
+                    passed_pawns,
+                };
+
+                self.entries[index] = Some(entry);
+                entry
+            },
+        }
+    }
+}
+
+impl ChessBoard {
+    // Scored from white's point of view.
+    pub(super) fn evaluate_pawn_structure(&self, pawn_hash_table: &mut PawnHashTable) -> TaperedScore {
+        let pawn_entry = pawn_hash_table.probe(self);
+        let mut pawn_score = pawn_entry.score;
+
+        for &color in [true, false].iter() {
+            let passed_pawns = pawn_entry.passed_pawns & self.get_color_occupancy(color);
+
+            for square in get_squares(passed_pawns) {
+                let path = get_file_bitboard(square) & get_forward_ranks_bitboard(color, square);
+
+                if path & self.get_occupancy() == 0 {
+                    let bonus = FREE_PASSED_PAWN_BONUS[get_relative_rank(color, square)];
+
+                    pawn_score += if color { bonus } else { -bonus };
+                }
+            }
+        }
+
+        pawn_score
+    }
+
+    // Scored from white's point of view, along with every passed pawn on the board.
+    fn compute_pawn_structure(&self) -> (TaperedScore, Bitboard) {
+        let mut score = TaperedScore::default();
+        let mut passed_pawns = 0;
+
+        for &color in [true, false].iter() {
+            let (color_score, color_passed_pawns) = self.evaluate_pawns(color);
+
+            score += if color { color_score } else { -color_score };
+            passed_pawns |= color_passed_pawns;
+        }
+
+        (score, passed_pawns)
+    }
+
+    fn evaluate_pawns(&self, color: bool) -> (TaperedScore, Bitboard) {
+        let attack_tables = get_attack_tables();
+
+        let own_pawns = self.get_pieces(PAWN, color);
+        let enemy_pawns = self.get_pieces(PAWN, !color);
+
+        let enemy_pawn_attacks = self.get_pawn_attacked_squares(!color);
+
+        let mut score = TaperedScore::default();
+        let mut passed_pawns = 0;
+
+        for square in get_squares(own_pawns) {
+            let file = get_file_bitboard(square);
+            let adjacent_files = get_adjacent_files_bitboard(square);
+            let forward_ranks = get_forward_ranks_bitboard(color, square);
+
+            // Only the pawns behind another one on the file count, so a doubled pair costs once.
+            let doubled = own_pawns & file & forward_ranks != 0;
+
+            if doubled {
+                score += DOUBLED_PAWN_PENALTY;
+            }
+
+            if own_pawns & adjacent_files == 0 {
+                score += ISOLATED_PAWN_PENALTY;
+            } else if own_pawns & adjacent_files & !forward_ranks == 0
+                && enemy_pawn_attacks & square_bitboard(get_stop_square(color, square)) != 0 {
+                // Every neighbour has already moved past it and it can't advance safely either.
+                score += BACKWARD_PAWN_PENALTY;
+            }
+
+            if attack_tables.get_pawn_attacks(!color, square) & own_pawns != 0 {
+                score += CONNECTED_PAWN_BONUS;
+            }
+
+            if !doubled && enemy_pawns & (file | adjacent_files) & forward_ranks == 0 {
+                score += PASSED_PAWN_BONUS[get_relative_rank(color, square)];
+                passed_pawns |= square_bitboard(square);
+            }
+        }
+
+        (score, passed_pawns)
+    }
+
+    pub(super) fn get_pawn_attacked_squares(&self, color: bool) -> Bitboard {
+        let attack_tables = get_attack_tables();
+
+        get_squares(self.get_pieces(PAWN, color))
+            .fold(0, |attacks, square| attacks | attack_tables.get_pawn_attacks(color, square))
+    }
+}
+
+pub(super) fn get_relative_rank(color: bool, square: usize) -> usize {
+    if color {
+        square / 8
+    } else {
+        7 - square / 8
+    }
+}
+
+fn get_stop_square(color: bool, square: usize) -> usize {
+    if color {
+        square + 8
+    } else {
+        square - 8
+    }
+}
+
+#[cfg(test)]
+mod pawn_structure_tests {
+    use chess_board::{ChessBoard, PawnHashTable};
+    use chess_board::evaluation::TaperedScore;
+    use chess_board::pawn_structure::{DOUBLED_PAWN_PENALTY, ISOLATED_PAWN_PENALTY, BACKWARD_PAWN_PENALTY,
+                                      CONNECTED_PAWN_BONUS, PASSED_PAWN_BONUS};
+    use bitboard::square_bitboard;
+
+    fn evaluate_white_pawns(fen_string: &str) -> (TaperedScore, u64) {
+        ChessBoard::from_fen(fen_string).unwrap().evaluate_pawns(true)
+    }
+
+    #[test]
+    fn pawn_weaknesses_are_recognised() {
+        // Doubled and isolated on the c file, only the rear pawn pays for the doubling.
+        let (score, _) = evaluate_white_pawns("4k3/3p4/8/8/8/2P5/2P5/4K3 w - - 0 1");
+        assert_eq!(score, DOUBLED_PAWN_PENALTY + ISOLATED_PAWN_PENALTY + ISOLATED_PAWN_PENALTY);
+
+        // The d pawn has been left behind by the e pawn and the c5 pawn watches its stop square.
+        let (score, _) = evaluate_white_pawns("4k3/5p2/8/2p5/4P3/3P4/8/4K3 w - - 0 1");
+        assert_eq!(score, BACKWARD_PAWN_PENALTY + CONNECTED_PAWN_BONUS);
+    }
+
+    #[test]
+    fn passed_pawns_are_found_and_scored_by_rank() {
+        let (score, passed_pawns) = evaluate_white_pawns("4k3/8/1P6/8/8/8/7P/4K3 w - - 0 1");
+
+        assert_eq!(passed_pawns, square_bitboard(41) | square_bitboard(15));
+        assert_eq!(score, PASSED_PAWN_BONUS[5] + PASSED_PAWN_BONUS[1] + ISOLATED_PAWN_PENALTY + ISOLATED_PAWN_PENALTY);
+
+        // An enemy pawn on a neighbouring file further up can still stop it.
+        let (_, passed_pawns) = evaluate_white_pawns("4k3/p7/1P6/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(passed_pawns, 0);
+    }
+
+    #[test]
+    fn evaluation_is_symmetric_and_cached() {
+        let mut pawn_hash_table = PawnHashTable::new();
+
+        let white_to_move = ChessBoard::from_fen("4k3/pp3p2/8/2P5/8/6P1/P4P2/4K3 w - - 0 1").unwrap();
+        let black_to_move = ChessBoard::from_fen("4k3/p4p2/6p1/8/2p5/8/PP3P2/4K3 b - - 0 1").unwrap();
+
+        let score = white_to_move.evaluate(&mut pawn_hash_table);
+
+        assert_eq!(score, black_to_move.evaluate(&mut pawn_hash_table));
+        assert_eq!(score, white_to_move.evaluate(&mut pawn_hash_table));
+    }
+}
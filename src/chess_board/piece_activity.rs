@@ -0,0 +1,187 @@
+use bitboard::{Bitboard, RANK_1, get_squares, square_bitboard, get_attack_tables, get_file_bitboard,
+               get_adjacent_files_bitboard, get_forward_ranks_bitboard};
+use chess_board::{ChessBoard, PAWN, KNIGHT, BISHOP, ROOK, QUEEN};
+use chess_board::evaluation::TaperedScore;
+use chess_board::pawn_structure::get_relative_rank;
+
+// Per safe square a piece can reach, counted from the number of squares a piece of the type
+// typically has, so that an average piece scores nothing.
+static KNIGHT_MOBILITY: (TaperedScore, i64) = (TaperedScore::new(4, 4), 4);
+static BISHOP_MOBILITY: (TaperedScore, i64) = (TaperedScore::new(5, 5), 6);
+static ROOK_MOBILITY: (TaperedScore, i64) = (TaperedScore::new(2, 4), 7);
+static QUEEN_MOBILITY: (TaperedScore, i64) = (TaperedScore::new(1, 2), 14);
+
+static BISHOP_PAIR_BONUS: TaperedScore = TaperedScore::new(30, 50);
+static ROOK_ON_OPEN_FILE_BONUS: TaperedScore = TaperedScore::new(25, 10);
+static ROOK_ON_SEMI_OPEN_FILE_BONUS: TaperedScore = TaperedScore::new(12, 6);
+static ROOK_ON_SEVENTH_RANK_BONUS: TaperedScore = TaperedScore::new(20, 30);
+static KNIGHT_OUTPOST_BONUS: TaperedScore = TaperedScore::new(20, 10);
+static TRAPPED_BISHOP_PENALTY: TaperedScore = TaperedScore::new(-100, -100);
+static TRAPPED_ROOK_PENALTY: TaperedScore = TaperedScore::new(-40, -10);
+
+// Squares as seen from white's side, a bishop that took on a7 gets shut in by b7-b6 and one on
+// h7 by g7-g6.
+static TRAPPED_BISHOP_SQUARES: [(usize, usize); 2] = [(48, 41), (55, 46)];
+
+impl ChessBoard {
+    // Scored from white's point of view.
+    pub(super) fn evaluate_piece_activity(&self) -> TaperedScore {
+        self.evaluate_pieces(true) - self.evaluate_pieces(false)
+    }
+
+    fn evaluate_pieces(&self, color: bool) -> TaperedScore {
+        let attack_tables = get_attack_tables();
+        let occupancy = self.get_occupancy();
+
+        let own_pawns = self.get_pieces(PAWN, color);
+        let enemy_pawns = self.get_pieces(PAWN, !color);
+
+        // Squares taken by own pieces or watched by enemy pawns don't count towards mobility.
+        let mobility_area = !self.get_color_occupancy(color) & !self.get_pawn_attacked_squares(!color);
+
+        let mut score = TaperedScore::default();
+
+        for square in get_squares(self.get_pieces(KNIGHT, color)) {
+            score += get_mobility_score(KNIGHT_MOBILITY, attack_tables.get_knight_attacks(square) & mobility_area);
+
+            // Backed by a pawn and out of reach of every enemy pawn.
+            let relative_rank = get_relative_rank(color, square);
+            let supported = attack_tables.get_pawn_attacks(!color, square) & own_pawns != 0;
+            let attackable = get_adjacent_files_bitboard(square) & get_forward_ranks_bitboard(color, square) & enemy_pawns != 0;
+
+            if (3..=5).contains(&relative_rank) && supported && !attackable {
+                score += KNIGHT_OUTPOST_BONUS;
+            }
+        }
+
+        let bishops = self.get_pieces(BISHOP, color);
+
+        if bishops.count_ones() >= 2 {
+            score += BISHOP_PAIR_BONUS;
+        }
+
+        for square in get_squares(bishops) {
+            score += get_mobility_score(BISHOP_MOBILITY, attack_tables.get_bishop_attacks(square, occupancy) & mobility_area);
+
+            for &(bishop_square, pawn_square) in TRAPPED_BISHOP_SQUARES.iter() {
+                if get_relative_square(color, square) == bishop_square
+                    && enemy_pawns & square_bitboard(get_relative_square(color, pawn_square)) != 0 {
+                    score += TRAPPED_BISHOP_PENALTY;
+                }
+            }
+        }
+
+        let king_square = get_relative_square(color, self.find_king_index(color));
+        let enemy_king_square = get_relative_square(color, self.find_king_index(!color));
+
+        for square in get_squares(self.get_pieces(ROOK, color)) {
+            let mobility = attack_tables.get_rook_attacks(square, occupancy) & mobility_area;
+            let file = get_file_bitboard(square);
+
+            score += get_mobility_score(ROOK_MOBILITY, mobility);
+
+            if (own_pawns | enemy_pawns) & file == 0 {
+                score += ROOK_ON_OPEN_FILE_BONUS;
+            } else if own_pawns & file == 0 {
+                score += ROOK_ON_SEMI_OPEN_FILE_BONUS;
+            }
+
+            // The seventh rank only pays off with enemy pawns left on it or the king cut off behind.
+            if get_relative_rank(color, square) == 6
+                && (enemy_king_square / 8 == 7 || enemy_pawns & (RANK_1 << (square / 8 * 8)) != 0) {
+                score += ROOK_ON_SEVENTH_RANK_BONUS;
+            }
+
+            if is_rook_trapped_by_king(king_square, get_relative_square(color, square), mobility) {
+                score += TRAPPED_ROOK_PENALTY;
+            }
+        }
+
+        for square in get_squares(self.get_pieces(QUEEN, color)) {
+            score += get_mobility_score(QUEEN_MOBILITY, attack_tables.get_queen_attacks(square, occupancy) & mobility_area);
+        }
+
+        score
+    }
+}
+
+fn get_mobility_score(mobility_weight: (TaperedScore, i64), mobility: Bitboard) -> TaperedScore {
+    let (weight, typical_mobility) = mobility_weight;
+
+    weight.scale(mobility.count_ones() as i64 - typical_mobility)
+}
+
+// Mirrors black's squares so that both sides can be treated as white.
+fn get_relative_square(color: bool, square: usize) -> usize {
+    if color {
+        square
+    } else {
+        square ^ 56
+    }
+}
+
+// A king that stepped aside on the back rank without castling locks in the rook on its wing.
+fn is_rook_trapped_by_king(king_square: usize, rook_square: usize, mobility: Bitboard) -> bool {
+    let king_file = king_square % 8;
+    let rook_file = rook_square % 8;
+
+    king_square / 8 == 0 && rook_square / 8 <= 1 && mobility.count_ones() <= 3
+        && ((king_file >= 4 && rook_file > king_file) || (king_file < 4 && rook_file < king_file))
+}
+
+#[cfg(test)]
+mod piece_activity_tests {
+    use chess_board::ChessBoard;
+    use chess_board::evaluation::TaperedScore;
+    use chess_board::piece_activity::{BISHOP_PAIR_BONUS, ROOK_ON_SEVENTH_RANK_BONUS, KNIGHT_OUTPOST_BONUS,
+                                      TRAPPED_BISHOP_PENALTY};
+
+    fn evaluate_pieces(fen_string: &str, color: bool) -> TaperedScore {
+        ChessBoard::from_fen(fen_string).unwrap().evaluate_pieces(color)
+    }
+
+    fn evaluate_white_pieces(fen_string: &str) -> i64 {
+        evaluate_pieces(fen_string, true).taper(24)
+    }
+
+    #[test]
+    fn pieces_are_rewarded_for_mobility_and_open_files() {
+        assert!(evaluate_white_pieces("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1")
+            > evaluate_white_pieces("4k3/8/8/8/8/8/8/N3K3 w - - 0 1"));
+
+        let open_file = evaluate_white_pieces("4k3/8/8/8/8/8/1P6/R3K3 w - - 0 1");
+        let semi_open_file = evaluate_white_pieces("4k3/p7/8/8/8/8/1P6/R3K3 w - - 0 1");
+        let closed_file = evaluate_white_pieces("4k3/p7/8/8/8/8/PP6/R3K3 w - - 0 1");
+
+        assert!(open_file > semi_open_file);
+        assert!(semi_open_file > closed_file);
+    }
+
+    #[test]
+    fn bishop_pair_seventh_rank_and_outposts() {
+        assert_eq!(evaluate_pieces("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1", true),
+                   evaluate_pieces("4k3/8/8/8/8/8/8/4KB2 w - - 0 1", true)
+                       + evaluate_pieces("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", true) + BISHOP_PAIR_BONUS);
+
+        // The rook reaches the same squares in both, but only one king is cut off on the back rank.
+        assert_eq!(evaluate_pieces("6k1/R7/8/8/8/8/8/4K3 w - - 0 1", true),
+                   evaluate_pieces("8/R7/6k1/8/8/8/8/4K3 w - - 0 1", true) + ROOK_ON_SEVENTH_RANK_BONUS);
+
+        // The e pawn could still drive the knight away, the a pawn can't.
+        assert_eq!(evaluate_pieces("4k3/p7/8/3N4/2P5/8/8/4K3 w - - 0 1", true),
+                   evaluate_pieces("4k3/4p3/8/3N4/2P5/8/8/4K3 w - - 0 1", true) + KNIGHT_OUTPOST_BONUS);
+    }
+
+    #[test]
+    fn trapped_pieces_are_penalised() {
+        let trapped_bishop = evaluate_pieces("4k3/B7/1p6/8/8/8/8/4K3 w - - 0 1", true);
+
+        assert!(trapped_bishop.taper(24) < TRAPPED_BISHOP_PENALTY.taper(24));
+
+        // Black's pieces are looked at from black's side of the board.
+        assert_eq!(evaluate_pieces("4k3/8/8/8/8/1P6/b7/4K3 w - - 0 1", false), trapped_bishop);
+
+        assert!(evaluate_white_pieces("4k3/8/8/8/8/8/5PPP/5K1R w - - 0 1")
+            < evaluate_white_pieces("4k3/8/8/8/8/8/5PPP/5RK1 w - - 0 1"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::cmp::max;
+use bitboard::{Bitboard, square_bitboard};
+use chess_board::{ChessBoard, get_piece_type, get_piece_value, PAWN, KING};
+use chess_move::Move;
+use move_generator::MoveGenerator;
+
+// There are never more captures on a square than there are pieces on the board.
+const MAX_EXCHANGES: usize = 32;
+
+impl ChessBoard {
+    /// Static exchange evaluation, the material in centipawns the side to move ends up with after
+    /// both sides have captured on the destination square for as long as it pays off, each with
+    /// its least valuable piece.
+    // Pieces leaving the square's lines uncover sliders behind them, which then join in.
+    pub fn see(&self, chess_move: Move) -> i64 {
+        let move_generator = MoveGenerator::new();
+
+        let location = chess_move.get_from();
+        let destination = chess_move.get_to();
+
+        let mut occupancy = self.get_occupancy() ^ square_bitboard(location);
+        let mut gains = [0; MAX_EXCHANGES];
+
+        let mut piece_on_square = self.squares[location];
+
+        if chess_move.is_en_passant() {
+            occupancy ^= square_bitboard(self.get_en_passant_capture_square(destination));
+        }
+
+        if chess_move.is_capture() {
+            gains[0] = get_piece_value(chess_move.captured_piece);
+        }
+
+        if chess_move.is_promotion() {
+            gains[0] += get_piece_value(chess_move.promotion) - get_piece_value(piece_on_square);
+            piece_on_square = chess_move.promotion;
+        }
+
+        let mut color = self.current_color;
+        let mut depth = 0;
+
+        while depth + 1 < MAX_EXCHANGES {
+            color = !color;
+
+            let attackers = move_generator.get_attackers(self, destination, color, occupancy) & occupancy;
+
+            let attacker = match self.get_least_valuable_piece(attackers) {
+                Some(attacker) => attacker,
+                None => break,
+            };
+
+            // The king may only take last, when nothing can take it back.
+            if get_piece_type(self.squares[attacker]) == KING
+                && move_generator.get_attackers(self, destination, !color, occupancy) & occupancy != 0 {
+                break;
+            }
+
+            depth += 1;
+            gains[depth] = get_piece_value(piece_on_square) - gains[depth - 1];
+
+            piece_on_square = self.squares[attacker];
+            occupancy ^= square_bitboard(attacker);
+        }
+
+        // Either side can stop capturing whenever going on would lose more.
+        while depth > 0 {
+            gains[depth - 1] = -max(-gains[depth - 1], gains[depth]);
+            depth -= 1;
+        }
+
+        gains[0]
+    }
+
+    fn get_least_valuable_piece(&self, pieces: Bitboard) -> Option<usize> {
+        (PAWN..=KING)
+            .map(|piece_type| pieces & self.piece_bitboards[piece_type])
+            .find(|&piece_type_pieces| piece_type_pieces != 0)
+            .map(|piece_type_pieces| piece_type_pieces.trailing_zeros() as usize)
+    }
+}
+
+#[cfg(test)]
+mod see_tests {
+    use chess_board::ChessBoard;
+    use move_generator::MoveGenerator;
+    use test_util::find_move;
+
+    fn see(fen_string: &str, move_notated: &str) -> i64 {
+        let mut test_board = ChessBoard::from_fen(fen_string).unwrap();
+
+        let chess_move = find_move(&MoveGenerator::new(), &mut test_board, move_notated);
+
+        test_board.see(chess_move)
+    }
+
+    #[test]
+    fn simple_exchanges() {
+        assert_eq!(see("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5"), 100);
+        assert_eq!(see("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1", "e4d5"), 210);
+        assert_eq!(see("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", "d1d5"), -850);
+        assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+
+        // Quiet moves onto an attacked square lose the piece.
+        assert_eq!(see("4k3/8/2p5/8/8/2N5/8/4K3 w - - 0 1", "c3e4"), 0);
+        assert_eq!(see("4k3/8/2p5/8/8/2N5/8/4K3 w - - 0 1", "c3d5"), -310);
+    }
+
+    #[test]
+    fn exchanges_with_x_rays() {
+        // The knight on d7 and the rook and queen lined up on the e file decide it.
+        assert_eq!(see("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "d3e5"), -210);
+
+        // The queen behind the rook wins the pawn in the end.
+        assert_eq!(see("4k3/8/3r4/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5"), 100);
+
+        // Unless black has a queen behind its rook as well.
+        assert_eq!(see("4k3/3q4/3r4/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5"), -400);
+
+        // The bishop behind the pawn joins in once the pawn has taken.
+        assert_eq!(see("4k3/3r4/3r4/8/3P4/2P5/1B6/4K3 b - - 0 1", "d6d4"), -400);
+    }
+
+    #[test]
+    fn promotions_and_the_king() {
+        assert_eq!(see("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"), 850);
+        assert_eq!(see("2k5/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"), -100);
+
+        // The king can't take back a protected queen.
+        assert_eq!(see("2k5/BP6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"), 850);
+    }
+}
@@ -0,0 +1,244 @@
+use bitboard::get_squares;
+use chess_board::{ChessBoard, get_castling_rook_squares, get_piece_type, PAWN};
+use chess_move::Move;
+
+struct ZobristKeys {
+    pieces: [[u64; 64]; 12],
+    black_to_move: u64,
+    castling_rights: [u64; 16],
+    en_passant_files: [u64; 8],
+}
+
+static ZOBRIST_KEYS: ZobristKeys = generate_zobrist_keys();
+
+const fn split_mix(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+    let mut key = state;
+    key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    (state, key ^ (key >> 31))
+}
+
+const fn generate_zobrist_keys() -> ZobristKeys {
+    let mut keys = ZobristKeys {
+        pieces: [[0; 64]; 12],
+        black_to_move: 0,
+        castling_rights: [0; 16],
+        en_passant_files: [0; 8],
+    };
+
+    let mut state = 0x0123_4567_89ab_cdef;
+    let mut key;
+
+    let mut piece = 0;
+
+    while piece < 12 {
+        let mut square = 0;
+
+        while square < 64 {
+            (state, key) = split_mix(state);
+            keys.pieces[piece][square] = key;
+            square += 1;
+        }
+
+        piece += 1;
+    }
+
+    (state, key) = split_mix(state);
+    keys.black_to_move = key;
+
+    let mut castling_rights = 0;
+
+    while castling_rights < 16 {
+        (state, key) = split_mix(state);
+        keys.castling_rights[castling_rights] = key;
+        castling_rights += 1;
+    }
+
+    let mut file = 0;
+
+    while file < 8 {
+        (state, key) = split_mix(state);
+        keys.en_passant_files[file] = key;
+        file += 1;
+    }
+
+    keys
+}
+
+impl ChessBoard {
+    /// The Zobrist hash of the position, which only depends on the position and not on the moves
+    /// that led to it.
+    pub fn get_hash_key(&self) -> u64 {
+        self.hash_key
+    }
+
+    pub(super) fn compute_hash_key(&self) -> u64 {
+        let mut hash_key = self.castling_hash_key() ^ self.en_passant_hash_key();
+
+        if !self.current_color {
+            hash_key ^= ZOBRIST_KEYS.black_to_move;
+        }
+
+        for (square, &piece) in self.squares.iter().enumerate() {
+            if piece != '.' {
+                hash_key ^= piece_hash_key(square, piece);
+            }
+        }
+
+        hash_key
+    }
+
+    pub(crate) fn get_pawn_hash_key(&self) -> u64 {
+        self.pawn_hash_key
+    }
+
+    pub(super) fn compute_pawn_hash_key(&self) -> u64 {
+        get_squares(self.piece_bitboards[PAWN])
+            .fold(0, |pawn_hash_key, square| pawn_hash_key ^ piece_hash_key(square, self.squares[square]))
+    }
+
+    pub(super) fn hash_move(&mut self, chess_move: Move) {
+        let location = chess_move.get_from();
+        let destination = chess_move.get_to();
+        let location_piece = self.squares[location];
+
+        self.hash_piece(location, location_piece);
+
+        if chess_move.is_promotion() {
+            self.hash_piece(destination, chess_move.promotion);
+        } else {
+            self.hash_piece(destination, location_piece);
+        }
+
+        if chess_move.is_en_passant() {
+            let capture_square = self.get_en_passant_capture_square(destination);
+
+            self.hash_piece(capture_square, chess_move.captured_piece);
+        } else if chess_move.is_capture() {
+            self.hash_piece(destination, chess_move.captured_piece);
+        }
+
+        if chess_move.is_castling() {
+            let (rook_location, rook_destination) = get_castling_rook_squares(destination);
+            let rook = self.squares[rook_location];
+
+            self.hash_piece(rook_location, rook);
+            self.hash_piece(rook_destination, rook);
+        }
+    }
+
+    // Pawns go into a second key as well, which identifies the pawn structure on its own.
+    fn hash_piece(&mut self, square: usize, piece: char) {
+        let piece_hash_key = piece_hash_key(square, piece);
+
+        self.hash_key ^= piece_hash_key;
+
+        if get_piece_type(piece) == PAWN {
+            self.pawn_hash_key ^= piece_hash_key;
+        }
+    }
+
+    pub(super) fn hash_side_to_move(&mut self) {
+        self.hash_key ^= ZOBRIST_KEYS.black_to_move;
+    }
+
+    pub(super) fn update_castling_rights(&mut self, white_castling_rights: (bool, bool),
+                                         black_castling_rights: (bool, bool)) {
+        self.hash_key ^= self.castling_hash_key();
+
+        self.white_castling_rights = white_castling_rights;
+        self.black_castling_rights = black_castling_rights;
+
+        self.hash_key ^= self.castling_hash_key();
+    }
+
+    fn castling_hash_key(&self) -> u64 {
+        let castling_rights = self.white_castling_rights.0 as usize
+            | (self.white_castling_rights.1 as usize) << 1
+            | (self.black_castling_rights.0 as usize) << 2
+            | (self.black_castling_rights.1 as usize) << 3;
+
+        ZOBRIST_KEYS.castling_rights[castling_rights]
+    }
+
+    pub(super) fn en_passant_hash_key(&self) -> u64 {
+        match self.en_passant_square {
+            Some(en_passant_square) => ZOBRIST_KEYS.en_passant_files[en_passant_square % 8],
+            None => 0,
+        }
+    }
+}
+
+fn piece_hash_key(square: usize, piece: char) -> u64 {
+    let piece_type = get_piece_type(piece);
+
+    if piece.is_uppercase() {
+        ZOBRIST_KEYS.pieces[piece_type][square]
+    } else {
+        ZOBRIST_KEYS.pieces[piece_type + 6][square]
+    }
+}
+
+#[cfg(test)]
+mod zobrist_hashing_tests {
+    use chess_board::ChessBoard;
+    use move_generator::MoveGenerator;
+    use test_util::{for_each_position, play_moves};
+
+    #[test]
+    fn incremental_hash_keys_match_recomputation_in_perft_positions() {
+        for_each_position(3, |current_board| {
+            assert_eq!(current_board.hash_key, current_board.compute_hash_key());
+            assert_eq!(current_board.pawn_hash_key, current_board.compute_pawn_hash_key());
+        });
+    }
+
+    #[test]
+    fn null_moves_update_and_restore_the_position() {
+        let mut test_board = ChessBoard::new();
+
+        test_board.build_board_from_fen_string("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3".to_string());
+
+        let original_board = ChessBoard::from_fen(&test_board.to_fen()).unwrap();
+        let hash_key = test_board.get_hash_key();
+
+        test_board.make_null_move();
+
+        assert!(test_board.get_current_color());
+        assert!(test_board.is_after_null_move());
+        assert_eq!(test_board.hash_key, test_board.compute_hash_key());
+        assert_eq!(test_board.to_fen(), "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 4");
+
+        test_board.unmake_null_move();
+
+        assert!(test_board == original_board);
+        assert_eq!(test_board.get_hash_key(), hash_key);
+    }
+
+    #[test]
+    fn transpositions_share_hash_keys() {
+        let mut move_order_1 = ChessBoard::new();
+        let mut move_order_2 = ChessBoard::new();
+
+        move_order_1.build_board_from_fen_string("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        move_order_2.build_board_from_fen_string("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+
+        let move_generator = MoveGenerator::new();
+
+        play_moves(&move_generator, &mut move_order_1, &["g1f3", "g8f6", "b1c3"]);
+        play_moves(&move_generator, &mut move_order_2, &["b1c3", "g8f6", "g1f3"]);
+
+        assert_eq!(move_order_1.get_hash_key(), move_order_2.get_hash_key());
+
+        let mut with_en_passant = ChessBoard::new();
+        let mut without_en_passant = ChessBoard::new();
+
+        with_en_passant.build_board_from_fen_string("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1".to_string());
+        without_en_passant.build_board_from_fen_string("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1".to_string());
+
+        assert_ne!(with_en_passant.get_hash_key(), without_en_passant.get_hash_key());
+    }
+}
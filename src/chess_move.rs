@@ -0,0 +1,124 @@
+use std::fmt;
+use chess_board::get_square_in_chess_notation;
+use types::{Piece, PieceType, Square};
+
+pub const QUIET: u8 = 0;
+pub const DOUBLE_PAWN_PUSH: u8 = 1;
+pub const EN_PASSANT: u8 = 2;
+pub const CASTLING: u8 = 4;
+
+/// A move as generated by the [`MoveGenerator`](crate::MoveGenerator), written in UCI notation
+/// like "e2e4" or "e7e8q" when displayed. Castling is a king move of two squares.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub(crate) from: u8,
+    pub(crate) to: u8,
+    pub(crate) promotion: char,
+    pub(crate) flags: u8,
+    pub(crate) captured_piece: char,
+}
+
+impl Move {
+    pub(crate) fn new(from: usize, to: usize, captured_piece: char) -> Move {
+        Move {
+            from: from as u8,
+            to: to as u8,
+            promotion: '.',
+            flags: QUIET,
+            captured_piece,
+        }
+    }
+
+    /// The move that stands for no move at all, written "0000".
+    pub fn null() -> Move {
+        Move::new(0, 0, '.')
+    }
+
+    pub(crate) fn with_flags(self, flags: u8) -> Move {
+        Move {
+            flags,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_promotion(self, promotion: char) -> Move {
+        Move {
+            promotion,
+            ..self
+        }
+    }
+
+    pub(crate) fn get_from(&self) -> usize {
+        self.from as usize
+    }
+
+    pub(crate) fn get_to(&self) -> usize {
+        self.to as usize
+    }
+
+    /// The square the moving piece leaves.
+    pub fn get_from_square(&self) -> Square {
+        Square::from_index(self.get_from())
+    }
+
+    /// The square the moving piece lands on.
+    pub fn get_to_square(&self) -> Square {
+        Square::from_index(self.get_to())
+    }
+
+    /// The piece a pawn turns into, for promotions.
+    pub fn get_promotion_piece(&self) -> Option<PieceType> {
+        Piece::from_letter(self.promotion).map(|piece| piece.piece_type)
+    }
+
+    /// The piece taken, for captures including en passant.
+    pub fn get_captured_piece(&self) -> Option<PieceType> {
+        Piece::from_letter(self.captured_piece).map(|piece| piece.piece_type)
+    }
+
+    /// Whether this is the null move.
+    pub fn is_null(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// Whether the move takes a piece, en passant included.
+    pub fn is_capture(&self) -> bool {
+        self.captured_piece != '.'
+    }
+
+    /// Whether a pawn promotes.
+    pub fn is_promotion(&self) -> bool {
+        self.promotion != '.'
+    }
+
+    /// Whether a pawn captures en passant.
+    pub fn is_en_passant(&self) -> bool {
+        self.flags & EN_PASSANT != 0
+    }
+
+    /// Whether the king castles.
+    pub fn is_castling(&self) -> bool {
+        self.flags & CASTLING != 0
+    }
+
+    /// Whether a pawn moves two squares from its starting rank.
+    pub fn is_double_pawn_push(&self) -> bool {
+        self.flags & DOUBLE_PAWN_PUSH != 0
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_null() {
+            return write!(f, "0000");
+        }
+
+        write!(f, "{}{}", get_square_in_chess_notation(self.get_from()), get_square_in_chess_notation(self.get_to()))?;
+
+        if self.is_promotion() {
+            write!(f, "{}", self.promotion.to_ascii_lowercase())?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,55 +1,263 @@
-use chess_board::ChessBoard;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::thread::JoinHandle;
+use chess_board::{ChessBoard, FenError};
+use chess_move::Move;
 use move_generator::MoveGenerator;
-use searcher::Searcher;
+use perft::{PerftTable, DEFAULT_PERFT_HASH_SIZE, divide};
+use searcher::{Searcher, SearchInfo, SearchLimits};
 
+/// How a game ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameResult {
+    /// White checkmated black.
+    WhiteWins,
+    /// Black checkmated white.
+    BlackWins,
+    /// Neither side won.
+    Draw(DrawReason),
+}
+
+/// Why a game ended in a draw.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DrawReason {
+    /// The side to move has no legal move but isn't in check.
+    Stalemate,
+    /// The same position came up for the third time.
+    ThreefoldRepetition,
+    /// Fifty moves went by without a capture or pawn move.
+    FiftyMoveRule,
+    /// Neither side has the material left to mate.
+    InsufficientMaterial,
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameResult::WhiteWins         => write!(f, "1-0 White wins by checkmate"),
+            GameResult::BlackWins         => write!(f, "0-1 Black wins by checkmate"),
+            GameResult::Draw(draw_reason) => write!(f, "1/2-1/2 Draw by {}", draw_reason),
+        }
+    }
+}
+
+impl fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawReason::Stalemate            => write!(f, "stalemate"),
+            DrawReason::ThreefoldRepetition  => write!(f, "threefold repetition"),
+            DrawReason::FiftyMoveRule        => write!(f, "the fifty-move rule"),
+            DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
+        }
+    }
+}
+
+/// A game as a GUI drives it: a position set up and played on move by move, with searches running
+/// in the background.
+// The position history lives in the board's undo stack, which the moves of a `position` command
+// fill up and the searcher inherits along with the board. A position that failed to set up leaves
+// the game without one until the next good one arrives.
 pub struct Game {
-    current_position: ChessBoard,
-    legal_moves: Vec<ChessBoard>,
+    current_position: Option<ChessBoard>,
+    legal_moves: Vec<Move>,
     move_generator: MoveGenerator,
-    searcher: Searcher,
+    searcher: Option<Searcher>,
+    search_thread: Option<JoinHandle<Searcher>>,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game::new()
+    }
 }
 
 impl Game {
+    /// A game from the position every game starts from.
     pub fn new() -> Game {
-        Game {
-            current_position: ChessBoard::new(),
+        let searcher = Searcher::new();
+        let stop_signal = searcher.get_stop_signal();
+
+        let mut game = Game {
+            current_position: None,
             legal_moves: Vec::new(),
             move_generator: MoveGenerator::new(),
-            searcher: Searcher::new(),
-        }
+            searcher: Some(searcher),
+            search_thread: None,
+            stop_signal,
+        };
+
+        game.setup_initial_position();
+        game
     }
 
+    /// Starts over from the position every game starts from.
     pub fn setup_initial_position(&mut self) {
-        self.current_position.setup_initial_position();
-        self.legal_moves = self.move_generator.generate_next_moves_from_board(&self.current_position);
+        self.set_position(Some(ChessBoard::new()));
+    }
+
+    /// Starts over from a position given as FEN. When the FEN is rejected the game is left without
+    /// a position, so nothing gets searched or played on a position the GUI didn't ask for.
+    pub fn build_board_from_fen_string(&mut self, fen_string: &str) -> Result<(), FenError> {
+        match ChessBoard::from_fen(fen_string) {
+            Ok(current_position) => {
+                self.set_position(Some(current_position));
+                Ok(())
+            },
+            Err(fen_error) => {
+                self.set_position(None);
+                Err(fen_error)
+            },
+        }
     }
-    
-    pub fn build_board_from_fen_string(&mut self, fen_string: String) {
-        self.current_position.build_board_from_fen_string(fen_string);
-        self.legal_moves = self.move_generator.generate_next_moves_from_board(&self.current_position);
+
+    fn set_position(&mut self, current_position: Option<ChessBoard>) {
+        self.current_position = current_position;
+        self.legal_moves = match self.current_position.as_mut() {
+            Some(current_position) => self.move_generator.generate_next_moves_from_board(current_position),
+            None                   => Vec::new(),
+        };
+    }
+
+    /// Whether there is a position to play on, which is only missing after a rejected FEN.
+    pub fn has_valid_position(&self) -> bool {
+        self.current_position.is_some()
     }
 
-    pub fn accept_move(&mut self, move_notated: String) {
-        let mut move_legal = false;
+    /// Plays a move given in UCI notation like "e2e4". Returns false and leaves the position
+    /// untouched when the move isn't legal or there is no position.
+    pub fn accept_move(&mut self, move_notated: &str) -> bool {
+        let legal_move = self.legal_moves.iter()
+            .find(|legal_move| legal_move.to_string() == move_notated)
+            .cloned();
+
+        let current_position = match (legal_move, self.current_position.as_mut()) {
+            (Some(legal_move), Some(current_position)) => {
+                current_position.make_move(legal_move);
+                current_position
+            },
+            _ => return false,
+        };
+
+        self.legal_moves = self.move_generator.generate_next_moves_from_board(current_position);
+
+        true
+    }
 
-        for legal_move in &self.legal_moves {
-            if legal_move.last_move == move_notated {
-                move_legal = true;
-                self.current_position = legal_move.clone();
-                break;
+    /// How the game ended, or `None` while it is still going on or there is no position.
+    pub fn get_game_result(&self) -> Option<GameResult> {
+        self.current_position.as_ref()
+            .and_then(|current_position| get_game_result(current_position, &self.legal_moves, &self.move_generator))
+    }
+
+    /// Searches the current position on a background thread. Every finished iteration goes to
+    /// `report_iteration` and the best move goes to `report_best_move` once the search is done.
+    /// Without a position there is nothing to search and no move is reported right away.
+    pub fn start_search<F, G>(&mut self, search_limits: SearchLimits, report_iteration: F, report_best_move: G)
+        where F: FnMut(&SearchInfo) + Send + 'static,
+              G: FnOnce(Option<Move>) + Send + 'static {
+        self.stop_search();
+        self.stop_signal.store(false, Ordering::Relaxed);
+
+        let current_position = match self.current_position.clone() {
+            Some(current_position) => current_position,
+            None                   => return report_best_move(None),
+        };
+
+        let mut searcher = self.searcher.take().expect("Error: Searcher is not available!!!");
+        let stop_signal = self.stop_signal.clone();
+
+        self.search_thread = Some(thread::spawn(move || {
+            searcher.run_iterative_deepening(&current_position, &search_limits, report_iteration);
+
+            // An infinite search must not report its result before the GUI asks for it, the thread
+            // sleeps until `stop_search` wakes it up.
+            while search_limits.infinite && !stop_signal.load(Ordering::Relaxed) {
+                thread::park();
             }
+
+            report_best_move(searcher.get_best_move());
+
+            searcher
+        }));
+    }
+
+    /// Counts the positions below every legal move of the current position, which is what gets
+    /// compared against another engine when move generation goes wrong. The root moves are spread
+    /// over every available core and positions reached along several move orders are only counted
+    /// once. There are no moves to count below at depth 0 or without a position.
+    pub fn run_perft(&mut self, depth: usize) -> Vec<(Move, usize)> {
+        self.stop_search();
+
+        let current_position = match self.current_position.as_ref() {
+            Some(current_position) if depth > 0 => current_position,
+            _                                   => return Vec::new(),
+        };
+
+        let thread_count = thread::available_parallelism().map_or(1, |thread_count| thread_count.get());
+        let perft_table = PerftTable::new(DEFAULT_PERFT_HASH_SIZE);
+
+        divide(current_position, depth, thread_count, Some(&perft_table))
+    }
+
+    /// Forgets everything the searcher learned in earlier games.
+    pub fn new_game(&mut self) {
+        self.stop_search();
+
+        if let Some(searcher) = self.searcher.as_mut() {
+            searcher.new_game();
         }
+    }
+
+    /// Resizes the transposition table to the given number of megabytes.
+    pub fn set_hash_size(&mut self, size_in_megabytes: usize) {
+        self.stop_search();
 
-        if !move_legal {
-            panic!("Error: Illegal move!!!");
+        if let Some(searcher) = self.searcher.as_mut() {
+            searcher.resize_transposition_table(size_in_megabytes);
         }
+    }
+
+    /// Stops the background search, if any, and waits for it to report its move.
+    pub fn stop_search(&mut self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+
+        if let Some(search_thread) = self.search_thread.take() {
+            search_thread.thread().unpark();
 
-        self.legal_moves = self.move_generator.generate_next_moves_from_board(&self.current_position);
+            // A searcher whose thread panicked is lost along with its tables, the game carries on
+            // with a fresh one rather than going down too.
+            let searcher = search_thread.join().unwrap_or_else(|_| Searcher::new());
+
+            self.stop_signal = searcher.get_stop_signal();
+            self.searcher = Some(searcher);
+        }
     }
+}
 
-    pub fn find_best_move(&mut self) {
-        self.searcher.run_alpha_beta_pruning(&self.current_position, 4);
+/// How the game ended in the position, given its legal moves, or `None` while it is still going
+/// on.
+pub fn get_game_result(current_position: &ChessBoard, legal_moves: &[Move], move_generator: &MoveGenerator) -> Option<GameResult> {
+    // Mate on the last move before the fifty-move limit still counts, so it is looked at first.
+    if legal_moves.is_empty() {
+        if !move_generator.detect_check(current_position) {
+            return Some(GameResult::Draw(DrawReason::Stalemate));
+        } else if current_position.get_current_color() {
+            return Some(GameResult::BlackWins);
+        } else {
+            return Some(GameResult::WhiteWins);
+        }
+    }
 
-        println!("bestmove {}", self.searcher.next_move.last_move);
+    if current_position.count_repetitions() >= 2 {
+        Some(GameResult::Draw(DrawReason::ThreefoldRepetition))
+    } else if current_position.is_fifty_move_draw() {
+        Some(GameResult::Draw(DrawReason::FiftyMoveRule))
+    } else if current_position.is_insufficient_material() {
+        Some(GameResult::Draw(DrawReason::InsufficientMaterial))
+    } else {
+        None
     }
-}
\ No newline at end of file
+}
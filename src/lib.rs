@@ -0,0 +1,51 @@
+//! Arsenic, a chess engine that speaks UCI.
+//!
+//! Besides the `uci` and `selfplay` binaries, the engine can be embedded directly. A [`ChessBoard`]
+//! holds a position and plays moves on it, a [`MoveGenerator`] lists the legal moves, a
+//! [`Searcher`] picks the best one and a [`Game`] ties the three together the way a GUI drives the
+//! engine. Squares, pieces and colours are passed around as [`Square`], [`Piece`] and [`Color`].
+//!
+//! ```
+//! use arsenic_chess_engine::{ChessBoard, MoveGenerator, Searcher, SearchLimits, Square};
+//!
+//! let mut board = ChessBoard::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+//! let moves = MoveGenerator::new().generate_next_moves_from_board(&mut board);
+//!
+//! assert!(moves.iter().any(|chess_move| chess_move.get_to_square() == "c1".parse::<Square>().unwrap()));
+//!
+//! let mut searcher = Searcher::new();
+//! let mut deepest_iteration = 0;
+//! searcher.run_iterative_deepening(&board, &SearchLimits { depth: Some(3), ..SearchLimits::default() },
+//!                                  |search_info| deepest_iteration = search_info.depth);
+//!
+//! assert_eq!(deepest_iteration, 3);
+//!
+//! let best_move = searcher.get_best_move().expect("the position has legal moves");
+//! board.make_move(best_move);
+//! ```
+
+#![warn(missing_docs)]
+
+mod bench;
+mod bitboard;
+mod chess_board;
+mod chess_move;
+mod game;
+mod move_generator;
+mod move_ordering;
+mod perft;
+mod searcher;
+#[cfg(test)]
+mod test_util;
+mod transposition_table;
+mod types;
+mod uci_interface;
+
+pub use chess_board::{ChessBoard, FenError, PawnHashTable};
+pub use chess_move::Move;
+pub use game::{Game, GameResult, DrawReason, get_game_result};
+pub use move_generator::MoveGenerator;
+pub use perft::{PerftStatistics, PerftTable, DEFAULT_PERFT_HASH_SIZE, collect_perft_statistics, divide, run_perft};
+pub use searcher::{Score, Searcher, SearchInfo, SearchLimits, MAX_SEARCH_DEPTH};
+pub use types::{Color, ParseSquareError, Piece, PieceType, Square};
+pub use uci_interface::uci_loop;
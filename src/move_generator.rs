@@ -1,267 +1,316 @@
-use std::collections::HashMap;
-use chess_board::ChessBoard;
+use bitboard::{AttackTables, Bitboard, get_attack_tables, get_squares, square_bitboard};
+use chess_board::{ChessBoard, PAWN, KNIGHT, BISHOP, ROOK, QUEEN, KING};
+use chess_move::{Move, CASTLING, DOUBLE_PAWN_PUSH, EN_PASSANT};
+use types::{Color, Square};
 
-static N: i64 = -10;
-static E: i64 = 1;
-static S: i64 = 10;
-static W: i64 = -1;
-
-static A1: i64 = 91;
-static H1: i64 = 98;
-static A8: i64 = 21;
-static H8: i64 = 28;
+static WHITE_KING_SQUARE: usize = 4;
+static BLACK_KING_SQUARE: usize = 60;
 
+/// Generates the legal moves of a position.
 pub struct MoveGenerator {
-    directions: HashMap<char, Vec<i64>>,
+    attack_tables: &'static AttackTables,
+}
+
+impl Default for MoveGenerator {
+    fn default() -> MoveGenerator {
+        MoveGenerator::new()
+    }
 }
 
 impl MoveGenerator {
+    /// Creates a move generator, which is cheap since the attack tables are shared.
     pub fn new() -> MoveGenerator {
-        let mut directions: HashMap<char, Vec<i64>> = HashMap::new();
-
-        directions.insert('P', vec![N, N+N, N+W, N+E]);
-        directions.insert('N', vec![N+N+E, E+N+E, E+S+E, S+S+E, S+S+W, W+S+W, W+N+W, N+N+W]);
-        directions.insert('B', vec![N+E, S+E, S+W, N+W]);
-        directions.insert('R', vec![N, E, S, W]);
-        directions.insert('Q', vec![N, E, S, W, N+E, S+E, S+W, N+W]);
-        directions.insert('K', vec![N, E, S, W, N+E, S+E, S+W, N+W]);
-
         MoveGenerator {
-            directions,
+            attack_tables: get_attack_tables(),
         }
     }
 
-    pub fn generate_next_moves_from_board(&self, current_position: &ChessBoard) -> Vec<ChessBoard> {
-        let mut new_next_positions: Vec<ChessBoard> = Vec::new();
+    /// Every legal move in the position. The board is only changed while trying out moves and
+    /// is left as it was.
+    pub fn generate_next_moves_from_board(&self, current_position: &mut ChessBoard) -> Vec<Move> {
+        self.generate_moves(current_position, false)
+    }
 
-        // starts here
+    /// The legal captures and promotions in the position, which is what a quiescence search
+    /// looks at.
+    pub fn generate_capture_moves_from_board(&self, current_position: &mut ChessBoard) -> Vec<Move> {
+        self.generate_moves(current_position, true)
+    }
 
-        for (index, &piece) in current_position.board_vector.iter().enumerate() {
-            let index = index as i64;
+    fn generate_moves(&self, current_position: &mut ChessBoard, captures_only: bool) -> Vec<Move> {
+        let mut next_moves: Vec<Move> = Vec::with_capacity(64);
+        let current_color = current_position.get_current_color();
+        let occupancy = current_position.get_occupancy();
 
-            if !piece.is_uppercase() {
-                continue;
-            }
+        let targets = if captures_only {
+            current_position.get_color_occupancy(!current_color)
+        } else {
+            !current_position.get_color_occupancy(current_color)
+        };
 
-            let mut piece_directions = self.directions[&piece].clone();
-
-            while let Some(piece_direction) = piece_directions.pop() {
-                let mut destination = index;
-
-                loop {
-                    destination = destination + piece_direction;
-
-                    let mut new_position = current_position.clone();
-
-                    new_position.decrease_en_passant_lifetime();
-
-                    let destination_piece = current_position.get_square(destination);
-
-                    if destination_piece.is_whitespace() || destination_piece.is_uppercase() {
-                        break;
-                    }
-
-                    let mut pawn_promotion = false;
-
-                    if piece == 'P' {
-                        if (piece_direction == N || piece_direction == N + N) && destination_piece != '.' {
-                            break;
-                        }
-
-                        if piece_direction == N + N {
-                            if index < (A1 + N) || current_position.get_square(index + N) != '.' {
-                                break;
-                            } else {
-                                new_position.set_en_passant_square(119 - (index + N));
-                            }
-                        }
-
-                        if piece_direction == N + W || piece_direction == N + E {
-                            if new_position.is_en_passant_active() && destination as usize == new_position.en_passant_square {
-                                new_position.make_en_passant_move(index, destination);
-
-                                if !self.detect_check(&new_position) {
-                                    new_next_positions.push(new_position.clone_and_rotate());
-                                }
-                                break;
-                            }
-
-                            if destination_piece == '.' {
-                                break;
-                            }
-                        }
-
-                        if A8 <= destination && destination <= H8 {
-                            pawn_promotion = true;
-                        }
-                    }
-
-                    new_position.make_move(index, destination);
-
-                    if piece == 'K' {
-                        new_position.set_castling_rights(false, false);
-                    }
-
-                    if piece == 'R' {
-                        self.enforce_castling_rules(&current_position,
-                                                    &mut new_position,
-                                                    &mut new_next_positions,
-                                                    index, destination);
-                    }
-
-                    if !self.detect_check(&new_position) {
-                        if pawn_promotion {
-                            for promoted_piece in vec!['R', 'N', 'B', 'Q'] {
-                                new_position.promote_pawn(destination as usize, promoted_piece);
-                                new_next_positions.push(new_position.clone_and_rotate());
-                            }
-                        } else {
-                            new_next_positions.push(new_position.clone_and_rotate());
-                        }
-                    }
-
-                    if vec!['P', 'N', 'K'].contains(&piece) || destination_piece.is_lowercase() {
-                        break;
-                    }
+        for location in get_squares(current_position.get_pieces(PAWN, current_color)) {
+            self.generate_pawn_moves(current_position, location, captures_only, &mut next_moves);
+        }
+
+        for &piece_type in [KNIGHT, BISHOP, ROOK, QUEEN, KING].iter() {
+            for location in get_squares(current_position.get_pieces(piece_type, current_color)) {
+                let attacks = self.get_piece_attacks(piece_type, location, occupancy) & targets;
+
+                for destination in get_squares(attacks) {
+                    next_moves.push(Move::new(location, destination, current_position.get_square(destination)));
                 }
             }
         }
-        new_next_positions
+
+        if !captures_only {
+            self.generate_castling_moves(current_position, &mut next_moves);
+        }
+
+        self.retain_legal_moves(current_position, &mut next_moves);
+        next_moves
+    }
+
+    // Most pseudo-legal moves can't expose the king at all, so only king moves, en passant, pinned
+    // pieces and check evasions need a closer look, and only the odd cases are played out.
+    fn retain_legal_moves(&self, current_position: &mut ChessBoard, next_moves: &mut Vec<Move>) {
+        let current_color = current_position.get_current_color();
+        let occupancy = current_position.get_occupancy();
+        let king_square = current_position.find_king_index(current_color);
+
+        let checkers = self.get_attackers(current_position, king_square, !current_color, occupancy);
+        let pinned_pieces = self.get_pinned_pieces(current_position, king_square, current_color);
+
+        let evasion_squares = match checkers.count_ones() {
+            0 => !0,
+            1 => checkers | self.attack_tables.get_squares_between(king_square, checkers.trailing_zeros() as usize),
+            _ => 0,
+        };
+
+        next_moves.retain(|&chess_move| {
+            let location = chess_move.get_from();
+            let destination = chess_move.get_to();
+
+            if location == king_square {
+                let occupancy_without_king = occupancy & !square_bitboard(king_square);
+
+                self.get_attackers(current_position, destination, !current_color, occupancy_without_king) == 0
+            } else if chess_move.is_en_passant() || pinned_pieces & square_bitboard(location) != 0 {
+                self.is_move_legal(current_position, chess_move)
+            } else {
+                evasion_squares & square_bitboard(destination) != 0
+            }
+        });
+    }
+
+    fn get_piece_attacks(&self, piece_type: usize, location: usize, occupancy: Bitboard) -> Bitboard {
+        match piece_type {
+            KNIGHT => self.attack_tables.get_knight_attacks(location),
+            BISHOP => self.attack_tables.get_bishop_attacks(location, occupancy),
+            ROOK   => self.attack_tables.get_rook_attacks(location, occupancy),
+            QUEEN  => self.attack_tables.get_queen_attacks(location, occupancy),
+            KING   => self.attack_tables.get_king_attacks(location),
+            _      => panic!("Error: Can't match piece type to attacks!!!"),
+        }
     }
 
-    fn enforce_castling_rules(&self, current_position: &ChessBoard, new_position: &mut ChessBoard,
-                              new_next_positions: &mut Vec<ChessBoard>,
-                              index: i64, destination: i64) {
-        let mut board_after_castling = new_position.clone();
-        let mut castling_made = false;
+    fn generate_pawn_moves(&self, current_position: &ChessBoard, location: usize,
+                           captures_only: bool, next_moves: &mut Vec<Move>) {
+        let current_color = current_position.get_current_color();
+        let occupancy = current_position.get_occupancy();
 
-        if !self.detect_check(&board_after_castling) && current_position.get_square(destination) == '.' {  
-            if index == A1 && new_position.get_left_castling() {
-                new_position.set_left_castling(false);
+        let (destination, starting_rank, promotion_rank) = if current_color {
+            (location + 8, 1, 7)
+        } else {
+            (location - 8, 6, 0)
+        };
 
-                if current_position.get_square(destination + E) == 'K' {
-                    castling_made = true;
+        // Promotions are kept even without a capture since they change material as well.
+        if occupancy & square_bitboard(destination) == 0 {
+            if destination / 8 == promotion_rank {
+                push_promotions(Move::new(location, destination, '.'), current_color, next_moves);
+            } else if !captures_only {
+                next_moves.push(Move::new(location, destination, '.'));
 
-                    board_after_castling.make_move(destination + E, destination + W);
+                let double_push_destination = 2 * destination - location;
+
+                if location / 8 == starting_rank && occupancy & square_bitboard(double_push_destination) == 0 {
+                    next_moves.push(Move::new(location, double_push_destination, '.')
+                        .with_flags(DOUBLE_PAWN_PUSH));
                 }
-            } else if index == H1 && new_position.get_right_castling() {
-                new_position.set_right_castling(false);
+            }
+        }
 
-                if current_position.get_square(destination + W) == 'K' {
-                    castling_made = true;
+        let attacks = self.attack_tables.get_pawn_attacks(current_color, location);
 
-                    board_after_castling.make_move(destination + W, destination + E);
-                }
-            }   
+        for destination in get_squares(attacks & current_position.get_color_occupancy(!current_color)) {
+            let capture_move = Move::new(location, destination, current_position.get_square(destination));
+
+            if destination / 8 == promotion_rank {
+                push_promotions(capture_move, current_color, next_moves);
+            } else {
+                next_moves.push(capture_move);
+            }
         }
 
-        if castling_made {
-            board_after_castling.set_castling_rights(false, false);
-            board_after_castling.add_castling_bonus();
+        if let Some(en_passant_square) = current_position.get_en_passant_square() {
+            if attacks & square_bitboard(en_passant_square) != 0 {
+                let captured_pawn = if current_color { 'p' } else { 'P' };
 
-            if !self.detect_check(&board_after_castling)
-                && !self.detect_rook_check(&new_position, destination) {
-                new_next_positions.push(board_after_castling.clone_and_rotate());
+                next_moves.push(Move::new(location, en_passant_square, captured_pawn)
+                    .with_flags(EN_PASSANT));
             }
         }
     }
 
-    fn find_king_index(&self, current_position: ChessBoard) -> i64 {
-        let mut king_index = 0;
+    fn generate_castling_moves(&self, current_position: &ChessBoard, next_moves: &mut Vec<Move>) {
+        let current_color = current_position.get_current_color();
+        let (queenside_castling, kingside_castling) = current_position.get_castling_rights(current_color);
+
+        let (king_square, king, rook) = if current_color {
+            (WHITE_KING_SQUARE, 'K', 'R')
+        } else {
+            (BLACK_KING_SQUARE, 'k', 'r')
+        };
+
+        if !(queenside_castling || kingside_castling) || current_position.get_square(king_square) != king {
+            return;
+        }
+
+        if self.is_square_under_attack(current_position, king_square, !current_color) {
+            return;
+        }
+
+        // The square the king lands on is checked like any other king move by the legality filter.
+        if kingside_castling
+            && current_position.get_square(king_square + 1) == '.'
+            && current_position.get_square(king_square + 2) == '.'
+            && current_position.get_square(king_square + 3) == rook
+            && !self.is_square_under_attack(current_position, king_square + 1, !current_color) {
+            next_moves.push(Move::new(king_square, king_square + 2, '.')
+                .with_flags(CASTLING));
+        }
+
+        if queenside_castling
+            && current_position.get_square(king_square - 1) == '.'
+            && current_position.get_square(king_square - 2) == '.'
+            && current_position.get_square(king_square - 3) == '.'
+            && current_position.get_square(king_square - 4) == rook
+            && !self.is_square_under_attack(current_position, king_square - 1, !current_color) {
+            next_moves.push(Move::new(king_square, king_square - 2, '.')
+                .with_flags(CASTLING));
+        }
+    }
+
+    fn is_move_legal(&self, current_position: &mut ChessBoard, chess_move: Move) -> bool {
+        let current_color = current_position.get_current_color();
+
+        current_position.make_move(chess_move);
+
+        let king_index = current_position.find_king_index(current_color);
+        let move_legal = !self.is_square_under_attack(current_position, king_index, !current_color);
+
+        current_position.unmake_move();
+
+        move_legal
+    }
+
+    // A piece is pinned when it is the only piece between its king and an enemy slider on that line.
+    fn get_pinned_pieces(&self, current_position: &ChessBoard, king_square: usize, color: bool) -> Bitboard {
+        let occupancy = current_position.get_occupancy();
+        let queens = current_position.get_pieces(QUEEN, !color);
+
+        let snipers = (self.attack_tables.get_rook_attacks(king_square, 0) & (current_position.get_pieces(ROOK, !color) | queens))
+            | (self.attack_tables.get_bishop_attacks(king_square, 0) & (current_position.get_pieces(BISHOP, !color) | queens));
+
+        let mut pinned_pieces = 0;
+
+        for sniper in get_squares(snipers) {
+            let blockers = self.attack_tables.get_squares_between(king_square, sniper) & occupancy;
 
-        for (index, &piece) in current_position.board_vector.iter().enumerate() {
-            if piece == 'K' {
-                king_index = index;
-                break;
+            if blockers.count_ones() == 1 {
+                pinned_pieces |= blockers & current_position.get_color_occupancy(color);
             }
         }
 
-        king_index as i64
+        pinned_pieces
     }
 
+    /// Whether the side to move is in check.
     pub fn detect_check(&self, current_position: &ChessBoard) -> bool {
-        let king_index = self.find_king_index(current_position.clone());
+        let current_color = current_position.get_current_color();
+        let king_index = current_position.find_king_index(current_color);
 
-        self.is_square_under_attack(&current_position, king_index)
+        self.is_square_under_attack(current_position, king_index, !current_color)
     }
 
-    fn detect_rook_check(&self, current_position: &ChessBoard, rook_index: i64) -> bool {
-        self.is_square_under_attack(&current_position, rook_index)
+    /// Whether any piece of the given colour attacks the square.
+    pub fn is_square_attacked(&self, current_position: &ChessBoard, square: Square, attacking_color: Color) -> bool {
+        self.is_square_under_attack(current_position, square.get_index(), attacking_color.is_white())
     }
 
-    fn is_square_under_attack(&self, current_position: &ChessBoard, square_index: i64) -> bool {
-        for &piece in ['P', 'N', 'B', 'R', 'Q', 'K'].into_iter() {
-            for &piece_direction in &self.directions[&piece] {
-                let mut destination = square_index;
-
-                loop {
-                    destination = destination + piece_direction;
-
-                    let destination_piece = current_position.get_square(destination);
-
-                    if destination_piece.is_whitespace() || destination_piece.is_uppercase() {
-                        break;
-                    }
-
-                    if piece == 'P' {
-                        if piece_direction == N || piece_direction == N + N {
-                            if destination_piece != '.' {
-                                break;
-                            }
-                        }
-
-                        if piece_direction == N + N {
-                            if square_index < (A1 + N) || current_position.get_square(square_index + N) != '.' {
-                                break;
-                            }
-                        }
-
-                        if piece_direction == N + W || piece_direction == N + E {
-                            if destination_piece == '.' {
-                                break;
-                            }
-                        }
-                    }
-
-                    if vec!['P', 'N', 'B', 'R', 'Q', 'K'].contains(&piece)
-                        && piece.to_lowercase().to_string() == destination_piece.to_string() {
-                        return true;
-                    }
-
-                    if vec!['P', 'N', 'K'].contains(&piece) || destination_piece.is_lowercase() {
-                        break;
-                    }
-                }
-            }
-        }
-        false
+    pub(crate) fn is_square_under_attack(&self, current_position: &ChessBoard, square_index: usize, attacking_color: bool) -> bool {
+        self.get_attackers(current_position, square_index, attacking_color, current_position.get_occupancy()) != 0
+    }
+
+    pub(crate) fn get_attackers(&self, current_position: &ChessBoard, square_index: usize, attacking_color: bool,
+                                occupancy: Bitboard) -> Bitboard {
+        let queens = current_position.get_pieces(QUEEN, attacking_color);
+        let diagonal_sliders = current_position.get_pieces(BISHOP, attacking_color) | queens;
+        let straight_sliders = current_position.get_pieces(ROOK, attacking_color) | queens;
+
+        // Attacking pawns stand where a pawn of the other colour on the square would capture.
+        (self.attack_tables.get_pawn_attacks(!attacking_color, square_index) & current_position.get_pieces(PAWN, attacking_color))
+            | (self.attack_tables.get_knight_attacks(square_index) & current_position.get_pieces(KNIGHT, attacking_color))
+            | (self.attack_tables.get_king_attacks(square_index) & current_position.get_pieces(KING, attacking_color))
+            | (self.attack_tables.get_bishop_attacks(square_index, occupancy) & diagonal_sliders)
+            | (self.attack_tables.get_rook_attacks(square_index, occupancy) & straight_sliders)
     }
 
-    pub fn calculate_perft_depth(&mut self, current_board: &ChessBoard, depth: usize, notate: bool) -> usize {
+    /// Counts the positions reached after the given number of moves. See
+    /// [`run_perft`](crate::run_perft) for a faster, multithreaded count and
+    /// [`divide`](crate::divide) for the count below every legal move.
+    pub fn calculate_perft_depth(&mut self, current_board: &ChessBoard, depth: usize) -> usize {
+        let mut current_board = current_board.clone();
+
+        self.perft(&mut current_board, depth)
+    }
+
+    fn perft(&self, current_board: &mut ChessBoard, depth: usize) -> usize {
         if depth == 0 {
-            1
-        } else {
-            let moves = self.generate_next_moves_from_board(current_board);
-            let mut nodes = 0;
+            return 1;
+        }
 
-            for some_move in moves {
-                let move_notation = if notate {
-                    some_move.last_move.clone()
-                } else {
-                    "None".to_string()
-                };
+        let moves = self.generate_next_moves_from_board(current_board);
 
-                let partial_nodes = self.calculate_perft_depth(&some_move, depth - 1, false);
+        if depth == 1 {
+            return moves.len();
+        }
 
-                if notate {
-                    println!("{}: {}", move_notation, partial_nodes);
-                }
+        let mut nodes = 0;
 
-                nodes += partial_nodes;
-            }
+        for some_move in moves {
+            current_board.make_move(some_move);
 
-            nodes
+            nodes += self.perft(current_board, depth - 1);
+
+            current_board.unmake_move();
         }
+
+        nodes
+    }
+}
+
+fn get_piece_of_color(piece: char, color: bool) -> char {
+    if color {
+        piece.to_ascii_uppercase()
+    } else {
+        piece.to_ascii_lowercase()
+    }
+}
+
+fn push_promotions(pawn_move: Move, color: bool, next_moves: &mut Vec<Move>) {
+    for &promoted_piece in ['Q', 'R', 'B', 'N'].iter() {
+        next_moves.push(pawn_move.with_promotion(get_piece_of_color(promoted_piece, color)));
     }
 }
 
@@ -278,17 +327,17 @@ mod perft_move_generation_tests {
         test_board.setup_initial_position();
 
         //depth0
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0, false), 1);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0), 1);
         //depth1
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1, false), 20);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1), 20);
         //depth2
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2, false), 400);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2), 400);
         //depth3
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3, false), 8902);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3), 8902);
         //depth4
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4, false), 197281);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4), 197281);
         //depth5
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 5, false), 4865609);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 5), 4865609);
     }
 
     #[test]
@@ -299,15 +348,15 @@ mod perft_move_generation_tests {
         test_board.setup_position_2();
 
         //depth0
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0, false), 1);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0), 1);
         //depth1
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1, false), 48);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1), 48);
         //depth2
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2, false), 2039);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2), 2039);
         //depth3
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3, false), 97862);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3), 97862);
         //depth4
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4, false), 4085603);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4), 4085603);
     }
 
     #[test]
@@ -318,19 +367,19 @@ mod perft_move_generation_tests {
         test_board.setup_position_3();
 
         //depth0
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0, false), 1);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0), 1);
         //depth1
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1, false), 14);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1), 14);
         //depth2
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2, false), 191);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2), 191);
         //depth3
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3, false), 2812);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3), 2812);
         //depth4
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4, false), 43238);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4), 43238);
         //depth5
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 5, false), 674624);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 5), 674624);
         //depth6
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 6, false), 11030083);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 6), 11030083);
     }
 
     #[test]
@@ -341,17 +390,17 @@ mod perft_move_generation_tests {
         test_board.setup_position_4();
 
         //depth0
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0, false), 1);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0), 1);
         //depth1
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1, false), 6);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1), 6);
         //depth2
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2, false), 264);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2), 264);
         //depth3
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3, false), 9467);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3), 9467);
         //depth4
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4, false), 422333);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4), 422333);
         //depth5
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 5, false), 15833292);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 5), 15833292);
     }
 
     #[test]
@@ -362,15 +411,15 @@ mod perft_move_generation_tests {
         test_board.setup_position_5();
 
         //depth0
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0, false), 1);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0), 1);
         //depth1
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1, false), 44);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1), 44);
         //depth2
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2, false), 1486);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2), 1486);
         //depth3
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3, false), 62379);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3), 62379);
         //depth4
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4, false), 2103487);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4), 2103487);
     }
 
     #[test]
@@ -381,14 +430,30 @@ mod perft_move_generation_tests {
         test_board.setup_position_6();
 
         //depth0
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0, false), 1);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 0), 1);
         //depth1
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1, false), 46);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 1), 46);
         //depth2
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2, false), 2079);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 2), 2079);
         //depth3
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3, false), 89890);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 3), 89890);
         //depth4
-        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4, false), 3894594);
+        assert_eq!(move_generator.calculate_perft_depth(&test_board, 4), 3894594);
+    }
+
+    #[test]
+    fn capture_generation_test() {
+        let move_generator = MoveGenerator::new();
+        let mut test_board = ChessBoard::new();
+
+        test_board.setup_initial_position();
+        assert_eq!(move_generator.generate_capture_moves_from_board(&mut test_board).len(), 0);
+
+        test_board.setup_position_2();
+        assert_eq!(move_generator.generate_capture_moves_from_board(&mut test_board).len(), 8);
+
+        // Quiet promotions are generated alongside captures, once for every promoted piece.
+        test_board.build_board_from_fen_string("8/4P3/8/8/8/3p4/2P1P3/k6K w - - 0 1".to_string());
+        assert_eq!(move_generator.generate_capture_moves_from_board(&mut test_board).len(), 6);
     }
 }
@@ -0,0 +1,217 @@
+use chess_board::{ChessBoard, get_piece_type, get_piece_value};
+use chess_move::Move;
+use searcher::MAX_PLY;
+
+const HASH_MOVE_SCORE: i64 = 1_000_000;
+const CAPTURE_SCORE: i64 = 100_000;
+const FIRST_KILLER_SCORE: i64 = 90_000;
+const SECOND_KILLER_SCORE: i64 = 80_000;
+const LOSING_CAPTURE_SCORE: i64 = -100_000;
+const HISTORY_LIMIT: i64 = 50_000;
+
+// Hash move first, then captures and promotions that don't lose material by MVV-LVA, then the
+// two killer moves of the ply, then quiet moves by how often they caused cutoffs elsewhere in
+// the tree, and finally the captures that do lose material.
+pub struct MoveOrdering {
+    killer_moves: Vec<[Move; 2]>,
+    history: Vec<[i64; 64]>,
+}
+
+impl Default for MoveOrdering {
+    fn default() -> MoveOrdering {
+        MoveOrdering::new()
+    }
+}
+
+impl MoveOrdering {
+    pub fn new() -> MoveOrdering {
+        MoveOrdering {
+            killer_moves: vec![[Move::null(); 2]; MAX_PLY],
+            history: vec![[0; 64]; 2 * 64],
+        }
+    }
+
+    // Killers only make sense for the current search, history is worth keeping around but
+    // should give way to newer evidence.
+    pub fn start_new_search(&mut self) {
+        for killer_moves in self.killer_moves.iter_mut() {
+            *killer_moves = [Move::null(); 2];
+        }
+
+        self.age_history();
+    }
+
+    pub fn clear(&mut self) {
+        self.start_new_search();
+
+        for history_scores in self.history.iter_mut() {
+            *history_scores = [0; 64];
+        }
+    }
+
+    pub fn order_moves(&self, current_node: &ChessBoard, next_moves: &mut [Move], hash_move: Move, ply: usize) {
+        next_moves.sort_by_cached_key(|&chess_move| -self.score_move(current_node, chess_move, hash_move, ply));
+    }
+
+    pub fn order_capture_moves(&self, current_node: &ChessBoard, capture_moves: &mut [Move]) {
+        capture_moves.sort_by_cached_key(|&capture_move| -score_capture_move(current_node, capture_move));
+    }
+
+    fn score_move(&self, current_node: &ChessBoard, chess_move: Move, hash_move: Move, ply: usize) -> i64 {
+        if chess_move == hash_move {
+            HASH_MOVE_SCORE
+        } else if !is_quiet(chess_move) && is_losing_capture(current_node, chess_move) {
+            LOSING_CAPTURE_SCORE + score_capture_move(current_node, chess_move)
+        } else if !is_quiet(chess_move) {
+            CAPTURE_SCORE + score_capture_move(current_node, chess_move)
+        } else if ply < MAX_PLY && chess_move == self.killer_moves[ply][0] {
+            FIRST_KILLER_SCORE
+        } else if ply < MAX_PLY && chess_move == self.killer_moves[ply][1] {
+            SECOND_KILLER_SCORE
+        } else {
+            self.history[self.get_history_index(current_node, chess_move)][chess_move.get_to()]
+        }
+    }
+
+    // Only quiet moves are remembered, captures are ordered well enough on their own.
+    pub fn store_cutoff_move(&mut self, current_node: &ChessBoard, chess_move: Move, depth: usize, ply: usize) {
+        if !is_quiet(chess_move) {
+            return;
+        }
+
+        if ply < MAX_PLY && self.killer_moves[ply][0] != chess_move {
+            self.killer_moves[ply][1] = self.killer_moves[ply][0];
+            self.killer_moves[ply][0] = chess_move;
+        }
+
+        let history_index = self.get_history_index(current_node, chess_move);
+        let history_score = &mut self.history[history_index][chess_move.get_to()];
+
+        *history_score += (depth * depth) as i64;
+
+        if *history_score > HISTORY_LIMIT {
+            self.age_history();
+        }
+    }
+
+    fn get_history_index(&self, current_node: &ChessBoard, chess_move: Move) -> usize {
+        let color_offset = if current_node.get_current_color() { 0 } else { 64 };
+
+        color_offset + chess_move.get_from()
+    }
+
+    fn age_history(&mut self) {
+        for history_scores in self.history.iter_mut() {
+            for history_score in history_scores.iter_mut() {
+                *history_score /= 2;
+            }
+        }
+    }
+}
+
+fn is_quiet(chess_move: Move) -> bool {
+    !chess_move.is_capture() && !chess_move.is_promotion()
+}
+
+// Taking a piece worth at least as much as the capturing one can't lose material, so the exchange
+// only has to be played out for the others.
+fn is_losing_capture(current_node: &ChessBoard, capture_move: Move) -> bool {
+    let attacker_value = get_piece_value(current_node.get_square(capture_move.get_from()));
+
+    attacker_value > get_piece_value(capture_move.captured_piece) && current_node.see(capture_move) < 0
+}
+
+// Most valuable victim first, and among those the least valuable attacker first. Promotions
+// count as winning the promoted piece.
+fn score_capture_move(current_node: &ChessBoard, capture_move: Move) -> i64 {
+    let attacker = get_piece_type(current_node.get_square(capture_move.get_from())) as i64;
+
+    let mut victim_score = 0;
+
+    if capture_move.is_capture() {
+        victim_score += get_piece_type(capture_move.captured_piece) as i64 + 1;
+    }
+
+    if capture_move.is_promotion() {
+        victim_score += get_piece_type(capture_move.promotion) as i64;
+    }
+
+    victim_score * 8 - attacker
+}
+
+#[cfg(test)]
+mod move_ordering_tests {
+    use chess_board::ChessBoard;
+    use chess_move::Move;
+    use move_generator::MoveGenerator;
+    use move_ordering::MoveOrdering;
+    use test_util::find_move;
+
+    // Captures by white: pawn takes queen, knight takes queen and pawn takes rook.
+    static CAPTURES: &str = "6k1/8/8/3q1r2/4P3/2N5/8/6K1 w - - 0 1";
+
+    fn get_ordered_moves(move_ordering: &MoveOrdering, current_board: &mut ChessBoard, hash_move: Move, ply: usize) -> Vec<String> {
+        let mut next_moves = MoveGenerator::new().generate_next_moves_from_board(current_board);
+
+        move_ordering.order_moves(current_board, &mut next_moves, hash_move, ply);
+        next_moves.iter().map(|chess_move| chess_move.to_string()).collect()
+    }
+
+    #[test]
+    fn the_hash_move_comes_first() {
+        let move_ordering = MoveOrdering::new();
+        let mut test_board = ChessBoard::from_fen(CAPTURES).unwrap();
+        let hash_move = find_move(&MoveGenerator::new(), &mut test_board, "g1h1");
+
+        assert_eq!(get_ordered_moves(&move_ordering, &mut test_board, hash_move, 0)[..2], ["g1h1", "e4d5"]);
+    }
+
+    #[test]
+    fn captures_go_by_most_valuable_victim_then_least_valuable_attacker() {
+        let move_ordering = MoveOrdering::new();
+        let mut test_board = ChessBoard::from_fen(CAPTURES).unwrap();
+
+        assert_eq!(get_ordered_moves(&move_ordering, &mut test_board, Move::null(), 0)[..3], ["e4d5", "c3d5", "e4f5"]);
+    }
+
+    #[test]
+    fn killer_moves_follow_the_captures() {
+        let move_generator = MoveGenerator::new();
+        let mut move_ordering = MoveOrdering::new();
+        let mut test_board = ChessBoard::from_fen(CAPTURES).unwrap();
+
+        for move_notated in ["c3b5", "g1h1"].iter() {
+            let killer_move = find_move(&move_generator, &mut test_board, move_notated);
+            move_ordering.store_cutoff_move(&test_board, killer_move, 1, 2);
+        }
+
+        // The most recent killer comes first.
+        assert_eq!(get_ordered_moves(&move_ordering, &mut test_board, Move::null(), 2)[..5],
+                   ["e4d5", "c3d5", "e4f5", "g1h1", "c3b5"]);
+    }
+
+    #[test]
+    fn quiet_moves_go_by_their_history() {
+        let move_generator = MoveGenerator::new();
+        let mut move_ordering = MoveOrdering::new();
+        let mut test_board = ChessBoard::new();
+
+        // Cutoffs deeper in the tree count for more, and at other plies they don't make killers here.
+        for &(move_notated, depth, ply) in [("g1f3", 2, 10), ("d2d4", 4, 11)].iter() {
+            let cutoff_move = find_move(&move_generator, &mut test_board, move_notated);
+            move_ordering.store_cutoff_move(&test_board, cutoff_move, depth, ply);
+        }
+
+        assert_eq!(get_ordered_moves(&move_ordering, &mut test_board, Move::null(), 0)[..2], ["d2d4", "g1f3"]);
+    }
+
+    #[test]
+    fn losing_captures_come_last() {
+        let move_ordering = MoveOrdering::new();
+        let mut test_board = ChessBoard::from_fen("6k1/2p5/3p4/8/8/8/8/3Q2K1 w - - 0 1").unwrap();
+
+        let ordered_moves = get_ordered_moves(&move_ordering, &mut test_board, Move::null(), 0);
+
+        assert_eq!(ordered_moves.last().map(String::as_str), Some("d1d6"));
+    }
+}
@@ -0,0 +1,380 @@
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+use bitboard::square_bitboard;
+use chess_board::ChessBoard;
+use chess_move::Move;
+use move_generator::MoveGenerator;
+use transposition_table::get_entry_count;
+
+/// The perft table size in megabytes the UCI `perft` command uses.
+pub const DEFAULT_PERFT_HASH_SIZE: usize = 64;
+
+// The depth is packed into the low bits of an entry, next to the node count.
+const DEPTH_BITS: u32 = 8;
+const DEPTH_MASK: u64 = (1 << DEPTH_BITS) - 1;
+
+/// What the moves leading to the positions at one depth did, counted like the published perft
+/// tables so that a move of the wrong kind is caught even when the node total happens to match.
+/// En passant captures count as captures as well, while double checks don't count as discovered
+/// checks.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PerftStatistics {
+    /// Positions reached.
+    pub nodes: usize,
+    /// Moves that took a piece.
+    pub captures: usize,
+    /// Captures en passant.
+    pub en_passants: usize,
+    /// Castling moves.
+    pub castles: usize,
+    /// Pawn promotions.
+    pub promotions: usize,
+    /// Moves that gave check.
+    pub checks: usize,
+    /// Checks given only by a piece other than the one that moved.
+    pub discovered_checks: usize,
+    /// Checks given by two pieces at once.
+    pub double_checks: usize,
+    /// Moves that gave mate.
+    pub checkmates: usize,
+}
+
+impl AddAssign for PerftStatistics {
+    fn add_assign(&mut self, other: PerftStatistics) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.en_passants += other.en_passants;
+        self.castles += other.castles;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+        self.discovered_checks += other.discovered_checks;
+        self.double_checks += other.double_checks;
+        self.checkmates += other.checkmates;
+    }
+}
+
+// The key is stored xored with the data, so that an entry torn apart by two threads writing it at
+// once no longer matches any position and is simply missed.
+#[derive(Default)]
+struct PerftEntry {
+    checked_key: AtomicU64,
+    data: AtomicU64,
+}
+
+/// Node counts below positions already counted, shared between all threads without locking.
+pub struct PerftTable {
+    entries: Vec<PerftEntry>,
+}
+
+impl PerftTable {
+    /// An empty table of the given number of megabytes.
+    pub fn new(size_in_megabytes: usize) -> PerftTable {
+        let entry_count = get_entry_count::<PerftEntry>(size_in_megabytes);
+
+        PerftTable {
+            entries: (0..entry_count).map(|_| PerftEntry::default()).collect(),
+        }
+    }
+
+    fn probe(&self, hash_key: u64, depth: usize) -> Option<usize> {
+        let entry = &self.entries[self.get_index(hash_key)];
+
+        let data = entry.data.load(Ordering::Relaxed);
+        let checked_key = entry.checked_key.load(Ordering::Relaxed);
+
+        if checked_key ^ data == hash_key && data & DEPTH_MASK == depth as u64 {
+            Some((data >> DEPTH_BITS) as usize)
+        } else {
+            None
+        }
+    }
+
+    fn store(&self, hash_key: u64, depth: usize, nodes: usize) {
+        let entry = &self.entries[self.get_index(hash_key)];
+        let data = (nodes as u64) << DEPTH_BITS | depth as u64;
+
+        entry.checked_key.store(hash_key ^ data, Ordering::Relaxed);
+        entry.data.store(data, Ordering::Relaxed);
+    }
+
+    fn get_index(&self, hash_key: u64) -> usize {
+        (hash_key as usize) & (self.entries.len() - 1)
+    }
+}
+
+/// Counts the positions reached below every legal move after the given number of moves in total,
+/// in the order the moves are generated, on up to `thread_count` threads.
+///
+/// # Panics
+///
+/// Panics when the depth is 0.
+// The root moves are handed out one at a time to the worker threads, so that a thread done with a
+// small subtree picks up the next move instead of waiting for the others.
+pub fn divide(current_board: &ChessBoard, depth: usize, thread_count: usize,
+              perft_table: Option<&PerftTable>) -> Vec<(Move, usize)> {
+    assert!(depth > 0, "Error: Perft divide needs a depth of at least 1!!!");
+    assert!((depth as u64) <= DEPTH_MASK, "Error: Perft depth is too large!!!");
+
+    let mut current_board = current_board.clone();
+    let root_moves = MoveGenerator::new().generate_next_moves_from_board(&mut current_board);
+
+    let next_move_index = AtomicUsize::new(0);
+    let mut node_counts = vec![0; root_moves.len()];
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..thread_count.clamp(1, root_moves.len().max(1)))
+            .map(|_| {
+                let mut current_board = current_board.clone();
+                let root_moves = &root_moves;
+                let next_move_index = &next_move_index;
+
+                scope.spawn(move || {
+                    let move_generator = MoveGenerator::new();
+                    let mut counted_moves = Vec::new();
+
+                    loop {
+                        let move_index = next_move_index.fetch_add(1, Ordering::Relaxed);
+
+                        if move_index >= root_moves.len() {
+                            return counted_moves;
+                        }
+
+                        current_board.make_move(root_moves[move_index]);
+                        let nodes = perft(&move_generator, &mut current_board, depth - 1, perft_table);
+                        current_board.unmake_move();
+
+                        counted_moves.push((move_index, nodes));
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (move_index, nodes) in worker.join().expect("Error: Perft thread panicked!!!") {
+                node_counts[move_index] = nodes;
+            }
+        }
+    });
+
+    root_moves.into_iter().zip(node_counts).collect()
+}
+
+/// Counts the positions reached after the given number of moves on up to `thread_count` threads,
+/// the same as the serial [`MoveGenerator::calculate_perft_depth`](crate::MoveGenerator::calculate_perft_depth).
+pub fn run_perft(current_board: &ChessBoard, depth: usize, thread_count: usize,
+                 perft_table: Option<&PerftTable>) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+
+    divide(current_board, depth, thread_count, perft_table)
+        .into_iter()
+        .map(|(_, nodes)| nodes)
+        .sum()
+}
+
+/// The statistics for every depth from 1 up to the given one, all gathered in a single walk.
+pub fn collect_perft_statistics(current_board: &ChessBoard, depth: usize) -> Vec<PerftStatistics> {
+    let mut current_board = current_board.clone();
+    let mut statistics = vec![PerftStatistics::default(); depth];
+
+    if depth > 0 {
+        collect_statistics(&MoveGenerator::new(), &mut current_board, &mut statistics);
+    }
+
+    statistics
+}
+
+fn collect_statistics(move_generator: &MoveGenerator, current_board: &mut ChessBoard,
+                      statistics: &mut [PerftStatistics]) {
+    for some_move in move_generator.generate_next_moves_from_board(current_board) {
+        current_board.make_move(some_move);
+
+        statistics[0] += get_move_statistics(move_generator, current_board, some_move);
+
+        if statistics.len() > 1 {
+            collect_statistics(move_generator, current_board, &mut statistics[1..]);
+        }
+
+        current_board.unmake_move();
+    }
+}
+
+// Looks at the position right after the move has been made.
+fn get_move_statistics(move_generator: &MoveGenerator, current_board: &mut ChessBoard, some_move: Move) -> PerftStatistics {
+    let mut statistics = PerftStatistics {
+        nodes: 1,
+        captures: (some_move.is_capture() || some_move.is_en_passant()) as usize,
+        en_passants: some_move.is_en_passant() as usize,
+        castles: some_move.is_castling() as usize,
+        promotions: some_move.is_promotion() as usize,
+        ..PerftStatistics::default()
+    };
+
+    let current_color = current_board.get_current_color();
+    let king_index = current_board.find_king_index(current_color);
+    let checkers = move_generator.get_attackers(current_board, king_index, !current_color, current_board.get_occupancy());
+
+    if checkers == 0 {
+        return statistics;
+    }
+
+    // A castling king gives check with the rook, which lands between the king's two squares.
+    let moved_piece_square = if some_move.is_castling() {
+        (some_move.get_from() + some_move.get_to()) / 2
+    } else {
+        some_move.get_to()
+    };
+
+    statistics.checks = 1;
+    if checkers.count_ones() > 1 {
+        statistics.double_checks = 1;
+    } else if checkers & !square_bitboard(moved_piece_square) != 0 {
+        statistics.discovered_checks = 1;
+    }
+
+    statistics.checkmates = move_generator.generate_next_moves_from_board(current_board).is_empty() as usize;
+
+    statistics
+}
+
+fn perft(move_generator: &MoveGenerator, current_board: &mut ChessBoard, depth: usize,
+         perft_table: Option<&PerftTable>) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = move_generator.generate_next_moves_from_board(current_board);
+
+    // Looking up the last ply would cost more than generating its moves.
+    if depth == 1 {
+        return moves.len();
+    }
+
+    let hash_key = current_board.get_hash_key();
+
+    if let Some(nodes) = perft_table.and_then(|perft_table| perft_table.probe(hash_key, depth)) {
+        return nodes;
+    }
+
+    let mut nodes = 0;
+
+    for some_move in moves {
+        current_board.make_move(some_move);
+        nodes += perft(move_generator, current_board, depth - 1, perft_table);
+        current_board.unmake_move();
+    }
+
+    if let Some(perft_table) = perft_table {
+        perft_table.store(hash_key, depth, nodes);
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod perft_tests {
+    use chess_board::ChessBoard;
+    use move_generator::MoveGenerator;
+    use perft::{PerftStatistics, PerftTable, collect_perft_statistics, divide, run_perft};
+    use test_util::get_perft_positions;
+
+    // Positions in EPD form, each followed by the node counts it should have as ";D<depth> <nodes>".
+    static PERFT_SUITE: &str = include_str!("perft_suite.epd");
+
+    #[allow(clippy::too_many_arguments)]
+    fn statistics(nodes: usize, captures: usize, en_passants: usize, castles: usize, promotions: usize,
+                  checks: usize, discovered_checks: usize, double_checks: usize, checkmates: usize) -> PerftStatistics {
+        PerftStatistics {
+            nodes,
+            captures,
+            en_passants,
+            castles,
+            promotions,
+            checks,
+            discovered_checks,
+            double_checks,
+            checkmates,
+        }
+    }
+
+    #[test]
+    fn parallel_and_hashed_perft_match_the_serial_path() {
+        let mut move_generator = MoveGenerator::new();
+        let perft_table = PerftTable::new(16);
+
+        for test_board in get_perft_positions() {
+            let serial_nodes = move_generator.calculate_perft_depth(&test_board, 3);
+
+            assert_eq!(run_perft(&test_board, 3, 1, None), serial_nodes);
+            assert_eq!(run_perft(&test_board, 3, 4, None), serial_nodes);
+            assert_eq!(run_perft(&test_board, 3, 4, Some(&perft_table)), serial_nodes);
+
+            // The counts below every root move come back in the same order whatever thread took them.
+            assert_eq!(divide(&test_board, 3, 4, Some(&perft_table)), divide(&test_board, 3, 1, None));
+        }
+    }
+
+    #[test]
+    fn hashed_perft_reaches_the_published_counts() {
+        let perft_table = PerftTable::new(16);
+        let mut test_board = ChessBoard::new();
+
+        test_board.setup_initial_position();
+        assert_eq!(run_perft(&test_board, 0, 4, Some(&perft_table)), 1);
+        assert_eq!(run_perft(&test_board, 5, 4, Some(&perft_table)), 4865609);
+
+        test_board.setup_position_2();
+        assert_eq!(run_perft(&test_board, 4, 4, Some(&perft_table)), 4085603);
+
+        test_board.setup_position_3();
+        assert_eq!(run_perft(&test_board, 6, 4, Some(&perft_table)), 11030083);
+    }
+
+    #[test]
+    fn statistics_of_the_initial_position() {
+        let mut test_board = ChessBoard::new();
+        test_board.setup_initial_position();
+
+        assert_eq!(collect_perft_statistics(&test_board, 5), vec![
+            statistics(20, 0, 0, 0, 0, 0, 0, 0, 0),
+            statistics(400, 0, 0, 0, 0, 0, 0, 0, 0),
+            statistics(8902, 34, 0, 0, 0, 12, 0, 0, 0),
+            statistics(197281, 1576, 0, 0, 0, 469, 0, 0, 8),
+            statistics(4865609, 82719, 258, 0, 0, 27351, 6, 0, 347),
+        ]);
+    }
+
+    #[test]
+    fn statistics_of_position_2() {
+        let mut test_board = ChessBoard::new();
+        test_board.setup_position_2();
+
+        assert_eq!(collect_perft_statistics(&test_board, 4), vec![
+            statistics(48, 8, 0, 2, 0, 0, 0, 0, 0),
+            statistics(2039, 351, 1, 91, 0, 3, 0, 0, 0),
+            statistics(97862, 17102, 45, 3162, 0, 993, 0, 0, 1),
+            statistics(4085603, 757163, 1929, 128013, 15172, 25523, 42, 6, 43),
+        ]);
+    }
+
+    #[test]
+    fn perft_suite() {
+        let perft_table = PerftTable::new(16);
+
+        for line in PERFT_SUITE.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let mut fields = line.split(';');
+            let fen_string = fields.next().unwrap().trim();
+            let test_board = ChessBoard::from_fen(fen_string).unwrap();
+
+            for field in fields {
+                let (depth, nodes) = field.trim().trim_start_matches('D').split_once(' ').unwrap();
+                let depth = depth.parse().unwrap();
+
+                assert_eq!(run_perft(&test_board, depth, 4, Some(&perft_table)), nodes.parse().unwrap(),
+                           "{} at depth {}", fen_string, depth);
+            }
+        }
+    }
+}
@@ -1,169 +1,754 @@
 use std::cmp::{max, min};
-use chess_board::ChessBoard;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use chess_board::{ChessBoard, PawnHashTable, get_piece_type, PAWN};
+use chess_move::Move;
 use move_generator::MoveGenerator;
+use move_ordering::MoveOrdering;
+use transposition_table::{Bound, TranspositionTable, DEFAULT_HASH_SIZE};
 
+pub const MATE_SCORE: i64 = 1_000_000;
+pub const MATE_THRESHOLD: i64 = MATE_SCORE - 1000;
+const INFINITE_SCORE: i64 = MATE_SCORE + 1;
+const ASPIRATION_WINDOW: i64 = 50;
+const ASPIRATION_MIN_DEPTH: usize = 4;
+const NULL_MOVE_MIN_DEPTH: usize = 3;
+const LATE_MOVE_MIN_DEPTH: usize = 3;
+const LATE_MOVE_MIN_INDEX: usize = 3;
+
+/// The deepest a search can go, in plies.
+pub const MAX_SEARCH_DEPTH: usize = 64;
+const MAX_EXTENSIONS: usize = 16;
+pub const MAX_PLY: usize = MAX_SEARCH_DEPTH + MAX_EXTENSIONS + 1;
+const DEFAULT_SEARCH_DEPTH: usize = 4;
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+const MOVE_OVERHEAD: u64 = 50;
+const DEFAULT_MOVE_TIME: u64 = 1000;
+const NODES_BETWEEN_LIMIT_CHECKS: u64 = 2048;
+
+/// When a search has to stop, as given to the UCI `go` command. Times are in milliseconds and a
+/// search without any limit goes to a fixed default depth.
+#[derive(Default)]
+pub struct SearchLimits {
+    /// Time left on white's clock.
+    pub white_time: Option<u64>,
+    /// Time left on black's clock.
+    pub black_time: Option<u64>,
+    /// Time added to white's clock after every move.
+    pub white_increment: Option<u64>,
+    /// Time added to black's clock after every move.
+    pub black_increment: Option<u64>,
+    /// Moves left until the next time control.
+    pub moves_to_go: Option<u64>,
+    /// Exactly how long to search.
+    pub move_time: Option<u64>,
+    /// How many plies deep to search at most.
+    pub depth: Option<usize>,
+    /// How many nodes to search at most.
+    pub nodes: Option<u64>,
+    /// Search until stopped through the stop signal.
+    pub infinite: bool,
+}
+
+impl SearchLimits {
+    fn is_unlimited(&self) -> bool {
+        self.white_time.is_none() && self.black_time.is_none() && self.move_time.is_none()
+            && self.depth.is_none() && self.nodes.is_none() && !self.infinite
+    }
+}
+
+/// How good a position is for the side to move.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Score {
+    /// An estimate in hundredths of a pawn.
+    Centipawns(i64),
+    /// A forced mate in this many moves, negative when the side to move is getting mated.
+    Mate(i64),
+}
+
+impl Score {
+    // Mates are counted in moves rather than plies.
+    fn from_search_score(score: i64) -> Score {
+        if score > MATE_THRESHOLD {
+            Score::Mate((MATE_SCORE - score + 1) / 2)
+        } else if score < -MATE_THRESHOLD {
+            Score::Mate(-((MATE_SCORE + score + 1) / 2))
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+/// What one finished iteration of [`Searcher::run_iterative_deepening`] found.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchInfo {
+    /// The depth the iteration searched to.
+    pub depth: usize,
+    /// The score of the position for the side to move.
+    pub score: Score,
+    /// The positions searched so far, over all iterations.
+    pub nodes: u64,
+    /// The time spent so far, over all iterations.
+    pub time: Duration,
+    /// The line the engine expects to be played, starting with its best move.
+    pub principal_variation: Vec<Move>,
+}
+
+/// Finds the best move in a position with an alpha-beta search, keeping its hash tables between
+/// searches.
 pub struct Searcher {
     move_generator: MoveGenerator,
-    pub next_move: ChessBoard,
-    pub game_status: String,
-    pub game_result: String,
+    pub(crate) next_move: Move,
+    nodes: u64,
+    start_time: Instant,
+    soft_time_limit: Option<Duration>,
+    hard_time_limit: Option<Duration>,
+    node_limit: Option<u64>,
+    stop_signal: Arc<AtomicBool>,
+    stopped: bool,
+    transposition_table: TranspositionTable,
+    move_ordering: MoveOrdering,
+    pawn_hash_table: PawnHashTable,
+    principal_variation_table: Vec<[Move; MAX_PLY]>,
+    principal_variation_length: [usize; MAX_PLY],
+    extensions: usize,
+}
+
+impl Default for Searcher {
+    fn default() -> Searcher {
+        Searcher::new()
+    }
 }
 
 impl Searcher {
+    /// Creates a searcher with a transposition table of the default size.
     pub fn new() -> Searcher {
         Searcher {
             move_generator: MoveGenerator::new(),
-            next_move: ChessBoard::new(),
-            game_status: String::new(),
-            game_result: String::new(),
+            next_move: Move::null(),
+            nodes: 0,
+            start_time: Instant::now(),
+            soft_time_limit: None,
+            hard_time_limit: None,
+            node_limit: None,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            stopped: false,
+            transposition_table: TranspositionTable::new(DEFAULT_HASH_SIZE),
+            move_ordering: MoveOrdering::new(),
+            pawn_hash_table: PawnHashTable::new(),
+            principal_variation_table: vec![[Move::null(); MAX_PLY]; MAX_PLY],
+            principal_variation_length: [0; MAX_PLY],
+            extensions: 0,
         }
     }
 
-    pub fn run_negamax(&mut self, current_node: &ChessBoard, depth: usize) {
-        self.negamax(current_node, depth, true);
+    /// The flag that stops a running search as soon as it is set, from any thread.
+    pub fn get_stop_signal(&self) -> Arc<AtomicBool> {
+        self.stop_signal.clone()
     }
 
-    fn negamax(&mut self, current_node: &ChessBoard, depth: usize, root: bool) -> i64 {
-        if depth == 0 {
-            return current_node.get_score();
+    /// The number of positions the last search looked at.
+    pub fn get_nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// The best move found by the last search, or `None` when the position had no legal move.
+    pub fn get_best_move(&self) -> Option<Move> {
+        if self.next_move.is_null() {
+            None
+        } else {
+            Some(self.next_move)
         }
+    }
 
-        let mut best_move = ChessBoard::new();
-        let mut max = i64::min_value() + 1;
+    /// Searches deeper and deeper until the limits are reached, handing what every finished
+    /// iteration found to `report_iteration`.
+    pub fn run_iterative_deepening<F>(&mut self, current_node: &ChessBoard, search_limits: &SearchLimits,
+                                      mut report_iteration: F) where F: FnMut(&SearchInfo) {
+        self.prepare_search(search_limits, current_node.get_current_color());
 
-        let next_moves: Vec<ChessBoard> = self.move_generator
-            .generate_next_moves_from_board(current_node);
+        let max_depth = if search_limits.is_unlimited() {
+            DEFAULT_SEARCH_DEPTH
+        } else {
+            min(search_limits.depth.unwrap_or(MAX_SEARCH_DEPTH), MAX_SEARCH_DEPTH)
+        };
 
-        if root && next_moves.len() == 0 {
-            self.game_status = "Finished".to_string();
+        let mut current_node = current_node.clone();
 
-            if self.move_generator.detect_check(current_node) {
-                if current_node.get_current_color() {
-                    self.game_result = "Black Won!".to_string();
-                } else {
-                    self.game_result = "White Won!".to_string();
-                }
-            } else {
-                self.game_result = "Draw".to_string();
+        // Falls back to the first legal move in case the first iteration gets interrupted.
+        let mut best_move = match self.move_generator.generate_next_moves_from_board(&mut current_node).first() {
+            Some(&first_move) => first_move,
+            None              => return,
+        };
+
+        let mut score = 0;
+
+        for depth in 1..=max_depth {
+            self.next_move = Move::null();
+
+            score = self.run_aspiration_search(&mut current_node, depth, score);
+
+            if self.stopped {
+                break;
             }
 
-            return 0
-        } else if next_moves.len() == 0 {
-            if self.move_generator.detect_check(current_node) {
-                return i64::min_value() + 10000 - depth as i64
-            } else {
-                return i64::max_value() - 10000 + depth as i64
+            best_move = self.next_move;
+            report_iteration(&self.get_search_info(depth, score));
+
+            if let Some(soft_time_limit) = self.soft_time_limit {
+                if self.start_time.elapsed() >= soft_time_limit {
+                    break;
+                }
             }
         }
 
-        for chess_move in &next_moves {
-            let chess_move_score = -self.negamax(chess_move, depth - 1, false);
+        self.next_move = best_move;
+    }
+
+    // Every search starts from a clean slate, whichever entry point it came through, so nothing
+    // of an earlier search's limits or stop carries over.
+    fn prepare_search(&mut self, search_limits: &SearchLimits, current_color: bool) {
+        self.start_time = Instant::now();
+        self.nodes = 0;
+        self.stopped = false;
+        self.extensions = 0;
+        self.next_move = Move::null();
+        self.node_limit = search_limits.nodes;
+        self.allocate_time(search_limits, current_color);
+        self.transposition_table.start_new_search();
+        self.move_ordering.start_new_search();
+    }
+
+    fn allocate_time(&mut self, search_limits: &SearchLimits, current_color: bool) {
+        self.soft_time_limit = None;
+        self.hard_time_limit = None;
+
+        if search_limits.infinite {
+            return;
+        }
+
+        if let Some(move_time) = search_limits.move_time {
+            let move_time = Duration::from_millis(move_time.saturating_sub(MOVE_OVERHEAD).max(1));
+
+            self.soft_time_limit = Some(move_time);
+            self.hard_time_limit = Some(move_time);
+
+            return;
+        }
 
-            if chess_move_score > max {
-                best_move = chess_move.clone();
-                max = chess_move_score;
+        let (time_left, increment) = if current_color {
+            (search_limits.white_time, search_limits.white_increment.unwrap_or(0))
+        } else {
+            (search_limits.black_time, search_limits.black_increment.unwrap_or(0))
+        };
+
+        if let Some(time_left) = time_left {
+            let available_time = time_left.saturating_sub(MOVE_OVERHEAD).max(1);
+            let moves_to_go = search_limits.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+
+            let budget = min(available_time / moves_to_go + increment * 3 / 4, available_time);
+            let maximum = min(budget * 3, available_time);
+
+            // A new iteration usually takes longer than all previous ones combined, so there is
+            // no point in starting one once half of the budget is spent.
+            self.soft_time_limit = Some(Duration::from_millis(budget / 2));
+            self.hard_time_limit = Some(Duration::from_millis(maximum));
+        } else if search_limits.white_time.is_some() || search_limits.black_time.is_some() {
+            // Only the opponent's clock was sent, which still asks for a timed search rather
+            // than one to the maximum depth.
+            self.soft_time_limit = Some(Duration::from_millis(DEFAULT_MOVE_TIME));
+            self.hard_time_limit = Some(Duration::from_millis(DEFAULT_MOVE_TIME));
+        }
+    }
+
+    fn check_search_limits(&mut self) {
+        if self.stop_signal.load(Ordering::Relaxed) {
+            self.stopped = true;
+        }
+
+        if let Some(node_limit) = self.node_limit {
+            if self.nodes >= node_limit {
+                self.stopped = true;
             }
         }
 
-        if root {
-            self.next_move = best_move.clone();
+        if self.nodes.is_multiple_of(NODES_BETWEEN_LIMIT_CHECKS) {
+            if let Some(hard_time_limit) = self.hard_time_limit {
+                if self.start_time.elapsed() >= hard_time_limit {
+                    self.stopped = true;
+                }
+            }
         }
+    }
+
+    // Searches a narrow window around the previous iteration's score first and widens it on the
+    // side that failed until the score lands inside.
+    fn run_aspiration_search(&mut self, current_node: &mut ChessBoard, depth: usize, previous_score: i64) -> i64 {
+        if depth < ASPIRATION_MIN_DEPTH || previous_score.abs() > MATE_THRESHOLD {
+            return self.alpha_beta_pruning(current_node, depth, 0, -INFINITE_SCORE, INFINITE_SCORE);
+        }
+
+        let mut alpha_delta = ASPIRATION_WINDOW;
+        let mut beta_delta = ASPIRATION_WINDOW;
+
+        loop {
+            let alpha = max(previous_score - alpha_delta, -INFINITE_SCORE);
+            let beta = min(previous_score + beta_delta, INFINITE_SCORE);
+
+            let score = self.alpha_beta_pruning(current_node, depth, 0, alpha, beta);
+
+            if self.stopped {
+                return score;
+            }
 
-        max
+            if score <= alpha {
+                alpha_delta *= 4;
+            } else if score >= beta {
+                beta_delta *= 4;
+            } else {
+                return score;
+            }
+        }
     }
 
+    /// Searches to exactly the given depth, at least 1 and at most 64, without any time or node
+    /// limit.
     pub fn run_alpha_beta_pruning(&mut self, current_node: &ChessBoard, depth: usize) {
-        let alpha = i64::min_value() + 1;
-        let beta = i64::max_value();
+        let depth = depth.clamp(1, MAX_SEARCH_DEPTH);
 
-        if depth % 2 == 0 {
-            self.alpha_beta_pruning(current_node, depth, alpha, beta, true, true, true);
+        self.prepare_search(&SearchLimits::default(), current_node.get_current_color());
+        self.alpha_beta_pruning(&mut current_node.clone(), depth, 0, -INFINITE_SCORE, INFINITE_SCORE);
+    }
+
+    fn get_search_info(&self, depth: usize, score: i64) -> SearchInfo {
+        SearchInfo {
+            depth,
+            score: Score::from_search_score(score),
+            nodes: self.nodes,
+            time: self.start_time.elapsed(),
+            principal_variation: self.principal_variation_table[0][..self.principal_variation_length[0]].to_vec(),
+        }
+    }
+
+    // Each node collects its best line from the move played and the line of the child below it,
+    // so the root ends up with the whole principal variation.
+    fn update_principal_variation(&mut self, ply: usize, chess_move: Move) {
+        let child_length = if ply + 1 < MAX_PLY {
+            self.principal_variation_length[ply + 1]
         } else {
-            self.alpha_beta_pruning(current_node, depth, alpha, beta, true, true, false);
+            0
+        };
+
+        self.principal_variation_table[ply][0] = chess_move;
+
+        for index in 0..child_length {
+            self.principal_variation_table[ply][index + 1] = self.principal_variation_table[ply + 1][index];
         }
+
+        self.principal_variation_length[ply] = child_length + 1;
     }
 
-    fn alpha_beta_pruning(&mut self, current_node: &ChessBoard, depth: usize, alpha: i64, beta: i64, maximizing_player: bool, root: bool, even: bool) -> i64 {
+    // Scores are always seen from the side to move, each ply hands the negated window down.
+    fn alpha_beta_pruning(&mut self, current_node: &mut ChessBoard, depth: usize, ply: usize, mut alpha: i64, beta: i64) -> i64 {
+        self.principal_variation_length[ply] = 0;
+
+        let root = ply == 0;
+        // Only nodes searched with an open window can end up on the principal variation, the root
+        // always is one.
+        let principal_variation_node = beta - alpha > 1;
+
+        if !root && is_draw_by_rule(current_node) {
+            return 0;
+        }
+
         if depth == 0 {
-            if even {
-                return current_node.get_score()
-            } else {
-                return -current_node.get_score()
-            }
+            return self.quiescence_search(current_node, alpha, beta);
+        }
+
+        self.nodes += 1;
+        self.check_search_limits();
 
+        if self.stopped {
+            return 0;
         }
 
-        let mut best_move_score = i64::min_value();
+        let hash_key = current_node.get_hash_key();
+        let mut hash_move = Move::null();
+
+        if let Some(entry) = self.transposition_table.probe(hash_key, ply) {
+            hash_move = entry.best_move;
+
+            // A cutoff here would leave the principal variation ending at this node, so nodes on
+            // it always search their moves.
+            if !principal_variation_node && entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact                         => return entry.score,
+                    Bound::Lower if entry.score >= beta  => return entry.score,
+                    Bound::Upper if entry.score <= alpha => return entry.score,
+                    _                                    => {},
+                }
+            }
+        }
+
+        let in_check = self.move_generator.detect_check(current_node);
+
+        // If passing the turn still leaves us above beta, a real move almost certainly would too.
+        // Not done in check, twice in a row, or with only pawns left where zugzwang is common.
+        // The evaluation is by far the most expensive condition, so it is left for last.
+        if !root && !in_check && depth >= NULL_MOVE_MIN_DEPTH && beta.abs() < MATE_THRESHOLD
+            && !current_node.is_after_null_move() && current_node.has_non_pawn_material(current_node.get_current_color())
+            && current_node.evaluate(&mut self.pawn_hash_table) >= beta {
+            let reduction = if depth >= 6 { 3 } else { 2 };
 
-        let next_moves: Vec<ChessBoard> = self.move_generator
+            current_node.make_null_move();
+            let null_move_score = -self.alpha_beta_pruning(current_node, depth - 1 - reduction, ply + 1, -beta, -beta + 1);
+            current_node.unmake_null_move();
+
+            if self.stopped {
+                return 0;
+            }
+
+            if null_move_score >= beta {
+                return beta;
+            }
+        }
+
+        let mut next_moves: Vec<Move> = self.move_generator
             .generate_next_moves_from_board(current_node);
 
-        if root && next_moves.len() == 0 {
-            self.game_status = "Finished".to_string();
+        if next_moves.is_empty() {
+            if in_check {
+                return -MATE_SCORE + ply as i64
+            } else {
+                return 0
+            }
+        }
 
-            if self.move_generator.detect_check(current_node) {
-                if current_node.get_current_color() {
-                    self.game_result = "Black Won!".to_string();
-                } else {
-                    self.game_result = "White Won!".to_string();
-                }
+        self.move_ordering.order_moves(current_node, &mut next_moves, hash_move, ply);
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITE_SCORE;
+        let mut best_move = Move::null();
+
+        for (move_index, &chess_move) in next_moves.iter().enumerate() {
+            current_node.make_move(chess_move);
+
+            let gives_check = self.move_generator.detect_check(current_node);
+
+            // Checks and pawns about to promote are searched a ply deeper so forcing lines get
+            // seen through, but only so often along one branch.
+            let extension = if self.extensions < MAX_EXTENSIONS
+                && (gives_check || is_pawn_push_to_seventh_rank(current_node, chess_move)) {
+                1
+            } else {
+                0
+            };
+
+            let new_depth = depth - 1 + extension;
+            self.extensions += extension;
+
+            // Quiet moves this far down the ordering rarely turn out best, so they get a shallower
+            // look first and the full depth only if they surprise us.
+            let reduction = if move_index >= LATE_MOVE_MIN_INDEX && depth >= LATE_MOVE_MIN_DEPTH && !in_check
+                && extension == 0 && !chess_move.is_capture() && !chess_move.is_promotion() {
+                if move_index >= 6 && depth >= 6 { 2 } else { 1 }
+            } else {
+                0
+            };
+
+            // Only the first move gets the full window, the rest just have to prove they are no
+            // better and are searched again in full if they turn out to be.
+            let mut chess_move_score = if move_index == 0 {
+                -self.alpha_beta_pruning(current_node, new_depth, ply + 1, -beta, -alpha)
             } else {
-                self.game_result = "Draw".to_string();
+                -self.alpha_beta_pruning(current_node, new_depth - reduction, ply + 1, -alpha - 1, -alpha)
+            };
+
+            if reduction > 0 && chess_move_score > alpha {
+                chess_move_score = -self.alpha_beta_pruning(current_node, new_depth, ply + 1, -alpha - 1, -alpha);
+            }
+
+            if move_index > 0 && chess_move_score > alpha && chess_move_score < beta {
+                chess_move_score = -self.alpha_beta_pruning(current_node, new_depth, ply + 1, -beta, -alpha);
+            }
+
+            self.extensions -= extension;
+            current_node.unmake_move();
+
+            if self.stopped {
+                break;
             }
 
-            return 0
-        } else if next_moves.len() == 0 {
-            if maximizing_player {
-                if self.move_generator.detect_check(current_node) {
-                    return i64::min_value() + 10000 - depth as i64
-                } else {
-                    return i64::max_value() - 10000 + depth as i64
+            if chess_move_score > best_score {
+                best_score = chess_move_score;
+                best_move = chess_move;
+            }
+
+            if chess_move_score > alpha {
+                alpha = chess_move_score;
+                self.update_principal_variation(ply, chess_move);
+
+                if root {
+                    self.next_move = chess_move;
                 }
+            }
+
+            if alpha >= beta {
+                self.move_ordering.store_cutoff_move(current_node, chess_move, depth, ply);
+                break;
+            }
+        }
+
+        if !self.stopped {
+            let bound = if best_score <= original_alpha {
+                Bound::Upper
+            } else if best_score >= beta {
+                Bound::Lower
             } else {
-                if self.move_generator.detect_check(current_node) {
-                    return i64::max_value() - 10000 + depth as i64
-                } else {
-                    return i64::min_value() + 10000 - depth as i64
-                }
+                Bound::Exact
+            };
+
+            self.transposition_table.store(hash_key, depth, bound, best_score, best_move, ply);
+        }
+
+        best_score
+    }
+
+    fn quiescence_search(&mut self, current_node: &mut ChessBoard, alpha: i64, beta: i64) -> i64 {
+        self.nodes += 1;
+        self.check_search_limits();
+
+        if self.stopped {
+            return 0;
+        }
+
+        let stand_pat = current_node.evaluate(&mut self.pawn_hash_table);
+
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+
+        let mut alpha = max(alpha, stand_pat);
+        let mut best_score = stand_pat;
+
+        let mut capture_moves = self.move_generator.generate_capture_moves_from_board(current_node);
+
+        self.move_ordering.order_capture_moves(current_node, &mut capture_moves);
+
+        for &capture_move in &capture_moves {
+            // Captures that lose material can't raise the score above standing pat.
+            if current_node.see(capture_move) < 0 {
+                continue;
+            }
+
+            current_node.make_move(capture_move);
+            let capture_move_score = -self.quiescence_search(current_node, -beta, -alpha);
+            current_node.unmake_move();
+
+            if self.stopped {
+                break;
+            }
+
+            if capture_move_score > best_score {
+                best_score = capture_move_score;
             }
+
+            if best_score >= beta {
+                break;
+            }
+
+            alpha = max(alpha, best_score);
         }
 
-        let mut v;
+        best_score
+    }
 
-        if maximizing_player {        
-            v = i64::min_value() + 1;
-            let mut alpha = alpha;
+    /// Forgets everything learned in earlier searches.
+    pub fn new_game(&mut self) {
+        self.transposition_table.clear();
+        self.pawn_hash_table.clear();
+        self.move_ordering.clear();
+    }
 
-            for chess_move in &next_moves {
-                v = max(v, self.alpha_beta_pruning(chess_move, depth - 1, alpha, beta, false, false, even));
-                alpha = max(alpha, v);
+    /// Resizes the transposition table to the given number of megabytes, which clears it.
+    pub fn resize_transposition_table(&mut self, size_in_megabytes: usize) {
+        self.transposition_table.resize(size_in_megabytes);
+    }
+}
 
-                if root {
-                    if v > best_move_score {
-                        best_move_score = v;
-                        self.next_move = chess_move.clone();
-                    }
-                }
+// Inside the search a single repetition is enough, since whoever could avoid the draw would have
+// done so the first time around.
+fn is_draw_by_rule(current_node: &ChessBoard) -> bool {
+    current_node.count_repetitions() > 0
+        || current_node.is_fifty_move_draw()
+        || current_node.is_insufficient_material()
+}
 
-                if beta <= alpha {
-                    break;
-                }
+// Any pawn on its seventh rank is passed, only the promotion square is left in front of it.
+fn is_pawn_push_to_seventh_rank(current_node: &ChessBoard, chess_move: Move) -> bool {
+    let destination = chess_move.get_to();
+    let seventh_rank = if current_node.get_current_color() { 1 } else { 6 };
+
+    get_piece_type(current_node.get_square(destination)) == PAWN && destination / 8 == seventh_rank
+}
+
+#[cfg(test)]
+mod search_tests {
+    use chess_board::ChessBoard;
+    use game::{get_game_result, GameResult};
+    use move_generator::MoveGenerator;
+    use searcher::{Searcher, SearchLimits, DEFAULT_MOVE_TIME, INFINITE_SCORE, MATE_SCORE, is_pawn_push_to_seventh_rank};
+    use test_util::find_move;
+
+    #[test]
+    fn search_prefers_mate_over_stalemate() {
+        let move_generator = MoveGenerator::new();
+        let mut searcher = Searcher::new();
+        let mut test_board = ChessBoard::new();
+
+        // Most queen moves that take h7 away from the king leave black without a legal move.
+        test_board.build_board_from_fen_string("7k/5K2/8/8/8/8/8/6Q1 w - - 0 1".to_string());
+
+        for depth in 1..=4 {
+            let score = searcher.alpha_beta_pruning(&mut test_board.clone(), depth, 0, -INFINITE_SCORE, INFINITE_SCORE);
+
+            if depth > 1 {
+                assert_eq!(score, MATE_SCORE - 1);
             }
-        } else {
-            v = i64::max_value();
-            let mut beta = beta;
+        }
 
-            for chess_move in &next_moves {
-                v = min(v, self.alpha_beta_pruning(chess_move, depth - 1, alpha, beta, true, false, even));
-                beta = min(beta, v);
+        test_board.make_move(searcher.next_move);
 
-                if beta <= alpha {
-                    break;
-                }
+        let legal_moves = move_generator.generate_next_moves_from_board(&mut test_board);
+        assert_eq!(get_game_result(&test_board, &legal_moves, &move_generator), Some(GameResult::WhiteWins));
+    }
+
+    #[test]
+    fn checks_are_followed_beyond_the_nominal_depth() {
+        let mut searcher = Searcher::new();
+        let mut test_board = ChessBoard::new();
+
+        // The rooks ladder the king up the board with four checks in a row, seven plies in all.
+        test_board.build_board_from_fen_string("8/8/8/7k/R7/8/8/1R5K w - - 0 1".to_string());
+
+        let score = searcher.alpha_beta_pruning(&mut test_board, 4, 0, -INFINITE_SCORE, INFINITE_SCORE);
+
+        assert!(score >= MATE_SCORE - 7, "{}", score);
+    }
+
+    #[test]
+    fn stalemate_is_scored_as_a_draw() {
+        let mut searcher = Searcher::new();
+        let mut test_board = ChessBoard::new();
+
+        test_board.build_board_from_fen_string("7k/5K2/6Q1/8/8/8/8/8 b - - 0 1".to_string());
+
+        assert_eq!(searcher.alpha_beta_pruning(&mut test_board, 3, 0, -INFINITE_SCORE, INFINITE_SCORE), 0);
+    }
+
+    #[test]
+    fn there_is_no_best_move_without_a_legal_move() {
+        let mut searcher = Searcher::new();
+        let test_board = ChessBoard::from_fen("7k/5K2/6Q1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        searcher.run_iterative_deepening(&test_board, &SearchLimits { depth: Some(2), ..SearchLimits::default() }, |_| {});
+        assert_eq!(searcher.get_best_move(), None);
+
+        searcher.run_alpha_beta_pruning(&test_board, 2);
+        assert_eq!(searcher.get_best_move(), None);
+    }
+
+    #[test]
+    fn fixed_depth_searches_start_over_after_a_limited_search() {
+        let mut searcher = Searcher::new();
+        let mut test_board = ChessBoard::new();
+
+        test_board.setup_initial_position();
+
+        searcher.run_iterative_deepening(&test_board, &SearchLimits { nodes: Some(100), ..SearchLimits::default() }, |_| {});
+        searcher.run_alpha_beta_pruning(&test_board, 3);
+
+        assert!(searcher.get_best_move().is_some());
+        assert!(searcher.get_nodes() > 100);
+
+        // Depths out of range are pulled back into it rather than finding nothing or overflowing.
+        searcher.run_alpha_beta_pruning(&test_board, 0);
+        assert!(searcher.get_best_move().is_some());
+
+        test_board.build_board_from_fen_string("7k/8/8/8/8/8/8/K7 w - - 0 1".to_string());
+        searcher.run_alpha_beta_pruning(&test_board, 100);
+        assert!(searcher.get_best_move().is_some());
+    }
+
+    #[test]
+    fn pawn_pushes_to_the_seventh_rank_are_extended_for_both_colors() {
+        let move_generator = MoveGenerator::new();
+
+        let positions = [
+            ("4k3/8/1P6/6P1/8/8/8/4K2R w - - 0 1", "b6b7", ["g5g6", "h1h7"]),
+            ("4k2r/8/8/8/6p1/1p6/8/4K3 b - - 0 1", "b3b2", ["g4g3", "h8h2"]),
+        ];
+
+        for &(fen_string, extended_move, other_moves) in positions.iter() {
+            let mut test_board = ChessBoard::from_fen(fen_string).unwrap();
+
+            // The search asks once the move has been made.
+            let chess_move = find_move(&move_generator, &mut test_board, extended_move);
+            test_board.make_move(chess_move);
+            assert!(is_pawn_push_to_seventh_rank(&test_board, chess_move), "{}", extended_move);
+            test_board.unmake_move();
+
+            for move_notated in other_moves.iter() {
+                let chess_move = find_move(&move_generator, &mut test_board, move_notated);
+                test_board.make_move(chess_move);
+                assert!(!is_pawn_push_to_seventh_rank(&test_board, chess_move), "{}", move_notated);
+                test_board.unmake_move();
             }
         }
+    }
+
+    #[test]
+    fn time_is_allocated_from_the_movers_clock() {
+        let mut searcher = Searcher::new();
+        let time_limits = |searcher: &Searcher| (searcher.soft_time_limit.map(|limit| limit.as_millis()),
+                                                 searcher.hard_time_limit.map(|limit| limit.as_millis()));
+
+        searcher.allocate_time(&SearchLimits { move_time: Some(1000), ..SearchLimits::default() }, true);
+        assert_eq!(time_limits(&searcher), (Some(950), Some(950)));
+
+        // 9950 ms over the default 30 moves plus three quarters of the increment.
+        searcher.allocate_time(&SearchLimits { white_time: Some(10000), white_increment: Some(100), black_time: Some(500),
+                                               ..SearchLimits::default() }, true);
+        assert_eq!(time_limits(&searcher), (Some(203), Some(1218)));
+
+        searcher.allocate_time(&SearchLimits { white_time: Some(500), black_time: Some(10050), moves_to_go: Some(10),
+                                               ..SearchLimits::default() }, false);
+        assert_eq!(time_limits(&searcher), (Some(500), Some(3000)));
+
+        searcher.allocate_time(&SearchLimits { black_time: Some(1000), ..SearchLimits::default() }, true);
+        assert_eq!(time_limits(&searcher), (Some(DEFAULT_MOVE_TIME as u128), Some(DEFAULT_MOVE_TIME as u128)));
 
-        v
+        searcher.allocate_time(&SearchLimits { depth: Some(5), ..SearchLimits::default() }, true);
+        assert_eq!(time_limits(&searcher), (None, None));
     }
 
+    #[test]
+    fn principal_variation_survives_transposition_table_hits() {
+        let mut searcher = Searcher::new();
+        let test_board = ChessBoard::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3").unwrap();
+        let search_limits = SearchLimits { depth: Some(4), ..SearchLimits::default() };
+
+        // Searching the same position again finds every node of the first search in the table.
+        for _ in 0..2 {
+            let mut principal_variations = Vec::new();
+
+            searcher.run_iterative_deepening(&test_board, &search_limits,
+                                             |search_info| principal_variations.push(search_info.principal_variation.clone()));
+
+            for (depth, principal_variation) in principal_variations.iter().enumerate().skip(2) {
+                assert!(principal_variation.len() > 1, "depth {}: {:?}", depth + 1, principal_variation);
+            }
+        }
+    }
 }
@@ -0,0 +1,68 @@
+use chess_board::ChessBoard;
+use chess_move::Move;
+use move_generator::MoveGenerator;
+
+// The positions with published perft counts, which between them contain every kind of move.
+pub(crate) fn get_perft_positions() -> Vec<ChessBoard> {
+    let setups: [fn(&mut ChessBoard); 6] = [
+        ChessBoard::setup_initial_position,
+        ChessBoard::setup_position_2,
+        ChessBoard::setup_position_3,
+        ChessBoard::setup_position_4,
+        ChessBoard::setup_position_5,
+        ChessBoard::setup_position_6,
+    ];
+
+    setups.iter()
+        .map(|setup| {
+            let mut test_board = ChessBoard::new();
+            setup(&mut test_board);
+            test_board
+        })
+        .collect()
+}
+
+// Calls `f` on every position up to the given number of moves into each of the perft positions,
+// checking along the way that taking a move back restores the hash key.
+pub(crate) fn for_each_position<F>(depth: usize, mut f: F) where F: FnMut(&ChessBoard) {
+    let move_generator = MoveGenerator::new();
+
+    for mut test_board in get_perft_positions() {
+        walk_positions(&move_generator, &mut test_board, depth, &mut f);
+    }
+}
+
+fn walk_positions<F>(move_generator: &MoveGenerator, current_board: &mut ChessBoard, depth: usize, f: &mut F)
+    where F: FnMut(&ChessBoard) {
+    f(current_board);
+
+    if depth == 0 {
+        return;
+    }
+
+    for next_move in move_generator.generate_next_moves_from_board(current_board) {
+        let hash_key = current_board.get_hash_key();
+
+        current_board.make_move(next_move);
+        walk_positions(move_generator, current_board, depth - 1, f);
+        current_board.unmake_move();
+
+        assert_eq!(current_board.get_hash_key(), hash_key, "{}", next_move);
+    }
+}
+
+// Looks up a legal move by its UCI notation, panicking when there is none.
+pub(crate) fn find_move(move_generator: &MoveGenerator, current_board: &mut ChessBoard, move_notated: &str) -> Move {
+    move_generator.generate_next_moves_from_board(current_board)
+        .into_iter()
+        .find(|next_move| next_move.to_string() == move_notated)
+        .unwrap_or_else(|| panic!("{} is not a legal move", move_notated))
+}
+
+pub(crate) fn play_moves(move_generator: &MoveGenerator, current_board: &mut ChessBoard, moves_notated: &[&str]) {
+    for move_notated in moves_notated {
+        let next_move = find_move(move_generator, current_board, move_notated);
+
+        current_board.make_move(next_move);
+    }
+}
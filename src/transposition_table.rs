@@ -0,0 +1,198 @@
+use std::mem::size_of;
+use chess_move::Move;
+use searcher::MATE_THRESHOLD;
+
+pub const DEFAULT_HASH_SIZE: usize = 16;
+pub const MIN_HASH_SIZE: usize = 1;
+pub const MAX_HASH_SIZE: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+pub struct TranspositionEntry {
+    pub hash_key: u64,
+    pub best_move: Move,
+    pub score: i64,
+    pub depth: usize,
+    pub bound: Bound,
+    generation: u8,
+}
+
+pub struct TranspositionTable {
+    entries: Vec<Option<TranspositionEntry>>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    pub fn new(size_in_megabytes: usize) -> TranspositionTable {
+        let mut transposition_table = TranspositionTable {
+            entries: Vec::new(),
+            generation: 0,
+        };
+
+        transposition_table.resize(size_in_megabytes);
+        transposition_table
+    }
+
+    pub fn resize(&mut self, size_in_megabytes: usize) {
+        self.entries = vec![None; get_entry_count::<Option<TranspositionEntry>>(size_in_megabytes)];
+        self.generation = 0;
+    }
+
+    pub fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
+            *entry = None;
+        }
+
+        self.generation = 0;
+    }
+
+    pub fn start_new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn probe(&self, hash_key: u64, ply: usize) -> Option<TranspositionEntry> {
+        match self.entries[self.get_index(hash_key)] {
+            Some(entry) if entry.hash_key == hash_key => {
+                Some(TranspositionEntry {
+                    score: score_from_transposition_table(entry.score, ply),
+                    ..entry
+                })
+            },
+            _ => None,
+        }
+    }
+
+    pub fn store(&mut self, hash_key: u64, depth: usize, bound: Bound, score: i64, best_move: Move, ply: usize) {
+        let index = self.get_index(hash_key);
+
+        // Entries are replaced by deeper searches of any position, but stale entries from earlier
+        // searches give way to anything.
+        let replace = match self.entries[index] {
+            Some(entry) => entry.generation != self.generation || depth >= entry.depth,
+            None => true,
+        };
+
+        if replace {
+            self.entries[index] = Some(TranspositionEntry {
+                hash_key,
+                best_move,
+                score: score_to_transposition_table(score, ply),
+                depth,
+                bound,
+                generation: self.generation,
+            });
+        }
+    }
+
+    fn get_index(&self, hash_key: u64) -> usize {
+        (hash_key as usize) & (self.entries.len() - 1)
+    }
+}
+
+// How many entries of the given type fit into a hash table of the given number of megabytes,
+// which is clamped to the sizes the UCI option allows. Rounding down to a power of two lets the
+// index be computed with a mask.
+pub fn get_entry_count<T>(size_in_megabytes: usize) -> usize {
+    let size_in_megabytes = size_in_megabytes.clamp(MIN_HASH_SIZE, MAX_HASH_SIZE);
+    let maximum_entries = size_in_megabytes * 1024 * 1024 / size_of::<T>();
+
+    1 << (usize::BITS - 1 - maximum_entries.leading_zeros())
+}
+
+// Mate scores count plies from the root, but the same position can be reached at any ply, so
+// they are stored as distance to mate from the position itself.
+fn score_to_transposition_table(score: i64, ply: usize) -> i64 {
+    if score > MATE_THRESHOLD {
+        score + ply as i64
+    } else if score < -MATE_THRESHOLD {
+        score - ply as i64
+    } else {
+        score
+    }
+}
+
+fn score_from_transposition_table(score: i64, ply: usize) -> i64 {
+    if score > MATE_THRESHOLD {
+        score - ply as i64
+    } else if score < -MATE_THRESHOLD {
+        score + ply as i64
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod transposition_table_tests {
+    use chess_move::Move;
+    use searcher::MATE_SCORE;
+    use transposition_table::{Bound, TranspositionTable, MAX_HASH_SIZE, MIN_HASH_SIZE, get_entry_count,
+                              score_from_transposition_table, score_to_transposition_table};
+
+    #[test]
+    fn mate_scores_are_adjusted_to_the_ply_they_are_probed_at() {
+        // Mating in 3 plies from a node at ply 2 is mating in 5 plies from the root.
+        let mate_score = MATE_SCORE - 5;
+        let stored_score = score_to_transposition_table(mate_score, 2);
+
+        assert_eq!(stored_score, MATE_SCORE - 3);
+        assert_eq!(score_from_transposition_table(stored_score, 2), mate_score);
+        assert_eq!(score_from_transposition_table(stored_score, 6), MATE_SCORE - 9);
+
+        let mated_score = -MATE_SCORE + 4;
+        let stored_score = score_to_transposition_table(mated_score, 3);
+
+        assert_eq!(stored_score, -MATE_SCORE + 1);
+        assert_eq!(score_from_transposition_table(stored_score, 3), mated_score);
+        assert_eq!(score_from_transposition_table(stored_score, 1), -MATE_SCORE + 2);
+
+        assert_eq!(score_to_transposition_table(150, 7), 150);
+        assert_eq!(score_from_transposition_table(-150, 7), -150);
+
+        let mut transposition_table = TranspositionTable::new(MIN_HASH_SIZE);
+
+        transposition_table.store(42, 3, Bound::Exact, mate_score, Move::null(), 2);
+        assert_eq!(transposition_table.probe(42, 4).unwrap().score, MATE_SCORE - 7);
+    }
+
+    #[test]
+    fn tables_hold_a_power_of_two_entries_within_their_size() {
+        assert_eq!(get_entry_count::<[u8; 16]>(1), 65536);
+        assert_eq!(get_entry_count::<[u8; 24]>(1), 32768);
+        assert_eq!(get_entry_count::<[u8; 16]>(0), get_entry_count::<[u8; 16]>(MIN_HASH_SIZE));
+        assert_eq!(get_entry_count::<[u8; 16]>(usize::MAX), get_entry_count::<[u8; 16]>(MAX_HASH_SIZE));
+    }
+
+    #[test]
+    fn deeper_and_newer_entries_replace_older_ones() {
+        let mut transposition_table = TranspositionTable::new(MIN_HASH_SIZE);
+
+        // Both keys land on the same entry.
+        let hash_key = 7;
+        let colliding_key = hash_key + transposition_table.entries.len() as u64;
+
+        transposition_table.store(hash_key, 5, Bound::Exact, 10, Move::null(), 0);
+        transposition_table.store(colliding_key, 3, Bound::Exact, 20, Move::null(), 0);
+
+        assert_eq!(transposition_table.probe(hash_key, 0).unwrap().depth, 5);
+        assert!(transposition_table.probe(colliding_key, 0).is_none());
+
+        transposition_table.store(colliding_key, 6, Bound::Lower, 30, Move::null(), 0);
+
+        assert!(transposition_table.probe(hash_key, 0).is_none());
+        assert_eq!(transposition_table.probe(colliding_key, 0).unwrap().depth, 6);
+
+        // A shallower entry from a new search still pushes out one left over from the last.
+        transposition_table.start_new_search();
+        transposition_table.store(hash_key, 1, Bound::Upper, 40, Move::null(), 0);
+
+        let entry = transposition_table.probe(hash_key, 0).unwrap();
+        assert_eq!((entry.depth, entry.bound, entry.score), (1, Bound::Upper, 40));
+        assert!(transposition_table.probe(colliding_key, 0).is_none());
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+use std::ops::Not;
+use std::str::FromStr;
+use chess_board::{get_square_index_from_chess_notation, get_square_in_chess_notation};
+
+/// One of the two sides.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Color {
+    /// The side that moves first.
+    White,
+    /// The side that moves second.
+    Black,
+}
+
+impl Color {
+    // Inside the engine a colour is a bool that is true for white.
+    pub(crate) fn from_bool(is_white: bool) -> Color {
+        if is_white {
+            Color::White
+        } else {
+            Color::Black
+        }
+    }
+
+    pub(crate) fn is_white(self) -> bool {
+        self == Color::White
+    }
+}
+
+impl Not for Color {
+    type Output = Color;
+
+    fn not(self) -> Color {
+        Color::from_bool(!self.is_white())
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::White => write!(f, "white"),
+            Color::Black => write!(f, "black"),
+        }
+    }
+}
+
+/// The kind of a piece, regardless of its colour.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PieceType {
+    /// A pawn.
+    Pawn,
+    /// A knight.
+    Knight,
+    /// A bishop.
+    Bishop,
+    /// A rook.
+    Rook,
+    /// A queen.
+    Queen,
+    /// A king.
+    King,
+}
+
+impl PieceType {
+    fn get_letter(self) -> char {
+        match self {
+            PieceType::Pawn   => 'p',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Rook   => 'r',
+            PieceType::Queen  => 'q',
+            PieceType::King   => 'k',
+        }
+    }
+}
+
+/// A piece of either colour.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Piece {
+    /// The side the piece belongs to.
+    pub color: Color,
+    /// What kind of piece it is.
+    pub piece_type: PieceType,
+}
+
+impl Piece {
+    /// Creates a piece of the given colour and type.
+    pub fn new(color: Color, piece_type: PieceType) -> Piece {
+        Piece {
+            color,
+            piece_type,
+        }
+    }
+
+    // Inside the engine a piece is its FEN letter, uppercase for white and '.' for no piece.
+    pub(crate) fn from_letter(letter: char) -> Option<Piece> {
+        let piece_type = match letter.to_ascii_lowercase() {
+            'p' => PieceType::Pawn,
+            'n' => PieceType::Knight,
+            'b' => PieceType::Bishop,
+            'r' => PieceType::Rook,
+            'q' => PieceType::Queen,
+            'k' => PieceType::King,
+            _   => return None,
+        };
+
+        Some(Piece::new(Color::from_bool(letter.is_ascii_uppercase()), piece_type))
+    }
+
+    /// The piece's letter in FEN, uppercase for white.
+    pub fn get_letter(self) -> char {
+        if self.color.is_white() {
+            self.piece_type.get_letter().to_ascii_uppercase()
+        } else {
+            self.piece_type.get_letter()
+        }
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_letter())
+    }
+}
+
+/// A square on the board, written like "e4" when displayed or parsed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Square(u8);
+
+impl Square {
+    /// The square on the given file and rank, both counted from 0, so that a1 is `(0, 0)` and
+    /// h8 is `(7, 7)`. Returns `None` when either lies off the board.
+    pub fn new(file: u8, rank: u8) -> Option<Square> {
+        if file < 8 && rank < 8 {
+            Some(Square(rank * 8 + file))
+        } else {
+            None
+        }
+    }
+
+    /// The file counted from 0 for the a file.
+    pub fn get_file(self) -> u8 {
+        self.0 % 8
+    }
+
+    /// The rank counted from 0 for the first rank.
+    pub fn get_rank(self) -> u8 {
+        self.0 / 8
+    }
+
+    // Inside the engine a square is its index, counting from a1 = 0 along the ranks up to h8 = 63.
+    pub(crate) fn from_index(square: usize) -> Square {
+        assert!(square < 64, "Error: Can't match location to a square!!!");
+
+        Square(square as u8)
+    }
+
+    pub(crate) fn get_index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(square_notated: &str) -> Result<Square, ParseSquareError> {
+        get_square_index_from_chess_notation(square_notated)
+            .map(Square::from_index)
+            .ok_or_else(|| ParseSquareError(square_notated.to_string()))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", get_square_in_chess_notation(self.get_index()))
+    }
+}
+
+/// The text that failed to parse as a square.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseSquareError(pub String);
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a square", self.0)
+    }
+}
+
+#[cfg(test)]
+mod types_tests {
+    use chess_board::ChessBoard;
+    use chess_move::Move;
+    use move_generator::MoveGenerator;
+    use test_util::find_move;
+    use types::{Color, Piece, PieceType, Square};
+
+    fn square(square_notated: &str) -> Square {
+        square_notated.parse().unwrap()
+    }
+
+    #[test]
+    fn squares_convert_between_notation_and_coordinates() {
+        let square: Square = "e4".parse().unwrap();
+
+        assert_eq!(square, Square::new(4, 3).unwrap());
+        assert_eq!((square.get_file(), square.get_rank()), (4, 3));
+        assert_eq!(Square::new(7, 7).unwrap().to_string(), "h8");
+
+        assert!("i1".parse::<Square>().is_err());
+        assert!("e9".parse::<Square>().is_err());
+        assert_eq!(Square::new(8, 0), None);
+    }
+
+    #[test]
+    fn pieces_round_trip_through_their_letters() {
+        for &letter in ['P', 'n', 'B', 'r', 'Q', 'k'].iter() {
+            assert_eq!(Piece::from_letter(letter).unwrap().get_letter(), letter);
+        }
+
+        assert_eq!(Piece::from_letter('N'), Some(Piece::new(Color::White, PieceType::Knight)));
+        assert_eq!(Piece::from_letter('.'), None);
+        assert_eq!(!Color::White, Color::Black);
+    }
+
+    #[test]
+    fn positions_and_moves_are_described_with_typed_values() {
+        let mut test_board = ChessBoard::from_fen("r3k3/1P6/8/3pP3/8/8/8/4K2R w Kq d6 0 1").unwrap();
+
+        assert_eq!(test_board.get_side_to_move(), Color::White);
+        assert_eq!(test_board.get_piece(square("b7")), Some(Piece::new(Color::White, PieceType::Pawn)));
+        assert_eq!(test_board.get_piece(square("b6")), None);
+        assert_eq!(test_board.get_king_square(Color::Black), square("e8"));
+        assert_eq!(test_board.get_en_passant_target(), Some(square("d6")));
+
+        assert!(test_board.can_castle_kingside(Color::White) && !test_board.can_castle_queenside(Color::White));
+        assert!(!test_board.can_castle_kingside(Color::Black) && test_board.can_castle_queenside(Color::Black));
+
+        let move_generator = MoveGenerator::new();
+
+        let en_passant = find_move(&move_generator, &mut test_board, "e5d6");
+        assert_eq!((en_passant.get_from_square(), en_passant.get_to_square()), (square("e5"), square("d6")));
+        assert_eq!(en_passant.get_captured_piece(), Some(PieceType::Pawn));
+
+        let promotion = find_move(&move_generator, &mut test_board, "b7a8n");
+        assert_eq!(promotion.get_promotion_piece(), Some(PieceType::Knight));
+        assert_eq!(promotion.get_captured_piece(), Some(PieceType::Rook));
+
+        assert!(move_generator.is_square_attacked(&test_board, square("c8"), Color::White));
+        assert!(move_generator.is_square_attacked(&test_board, square("c8"), Color::Black));
+        assert!(!move_generator.is_square_attacked(&test_board, square("h4"), Color::Black));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "null move")]
+    fn the_null_move_cannot_be_played() {
+        let mut test_board = ChessBoard::new();
+
+        test_board.make_move(Move::null());
+    }
+}
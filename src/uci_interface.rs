@@ -1,9 +1,16 @@
 use std::io::{stdin, BufRead};
+use std::cmp::max;
 use std::str::SplitWhitespace;
+use std::time::{Duration, Instant};
+use bench::{run_bench, DEFAULT_BENCH_DEPTH};
+use chess_move::Move;
 use game::Game;
+use searcher::{Score, SearchInfo, SearchLimits};
+use transposition_table::{DEFAULT_HASH_SIZE, MIN_HASH_SIZE, MAX_HASH_SIZE};
 
 const ENGINE_NAME: &str = "Snow Crust";
 
+/// Reads UCI commands from standard input and answers them on standard output until told to quit.
 pub fn uci_loop() {
     let mut current_game = Game::new();
 
@@ -11,96 +18,266 @@ pub fn uci_loop() {
 
     for line in console_input.lock().lines() {
         let line = line.unwrap_or("null input".to_string());
-        let mut token_sequence = line.split_whitespace();
-
-        if let Some(first_token) = token_sequence.next() {
-            match first_token {
-                "uci"        => uci(),
-                "isready"    => println!("readyok"),
-                "ucinewgame" => reset_game(&current_game),
-                "position"   => set_position(&mut current_game, &mut token_sequence),
-                "go"         => go(&mut current_game),
-                "setoption"  => set_option(),
-                "quit"       => break,
-                _            => println!("Unrecognised Token: {}", first_token),
-            }
+
+        if !execute_command(&mut current_game, &line) {
+            break;
+        }
+    }
+}
+
+// Returns false once the GUI wants the engine to quit.
+fn execute_command(current_game: &mut Game, line: &str) -> bool {
+    let mut token_sequence = line.split_whitespace();
+
+    if let Some(first_token) = token_sequence.next() {
+        match first_token {
+            "uci"        => uci(),
+            "isready"    => println!("readyok"),
+            "ucinewgame" => reset_game(current_game),
+            "position"   => set_position(current_game, &mut token_sequence),
+            "go"         => go(current_game, &mut token_sequence),
+            "stop"       => current_game.stop_search(),
+            "setoption"  => set_option(current_game, &mut token_sequence),
+            "bench"      => bench(current_game, &mut token_sequence),
+            "divide"     => perft(current_game, &mut token_sequence),
+            "quit"       => {
+                current_game.stop_search();
+                return false;
+            },
+            _            => println!("Unrecognised Token: {}", first_token),
         }
     }
+
+    true
 }
 
 fn uci() {
     println!("id name {}", ENGINE_NAME);
     println!("id author Ugur Mislina Gul");
+    println!("option name Hash type spin default {} min {} max {}", DEFAULT_HASH_SIZE, MIN_HASH_SIZE, MAX_HASH_SIZE);
     println!("uciok");
 }
 
-fn reset_game(game: &Game) {
-
+fn reset_game(game: &mut Game) {
+    game.new_game();
 }
 
 fn set_position(game: &mut Game, token_sequence: &mut SplitWhitespace) {
-    match token_sequence.next() {
-        Some(argument) => {
-            match argument {
-                "fen"      => paraphrase_fen_token(game, token_sequence),
-                "startpos" => paraphrase_startpos_token(game, token_sequence),
-                _          => panic!("Error: Invalid fen string!!!"), 
-            }
-        },
-        None => panic!("Error: Invalid fen string!!!"),
-    }
-}
+    game.stop_search();
 
-fn paraphrase_fen_token(game: &mut Game, token_sequence: &mut SplitWhitespace) {
-    let mut fen_string = String::new();
+    let position_argument = token_sequence.next();
+    let mut fen_tokens = Vec::new();
     let mut moves = Vec::new();
+    let mut reading_moves = false;
 
-    while let Some(token) = token_sequence.next() {
+    for token in token_sequence {
         match token {
-            "moves" => {
-                while let Some(chess_move) = token_sequence.next() {
-                    moves.push(chess_move.to_string());
-                }
-            },
-            _ => {
-                fen_string.push_str(token);
-                fen_string.push(' ');
-            },
+            "moves" => reading_moves = true,
+            _ if reading_moves => moves.push(token),
+            _       => fen_tokens.push(token),
         }
     }
 
-    game.build_board_from_fen_string(fen_string);
+    let position_result = match position_argument {
+        Some("startpos") => {
+            game.setup_initial_position();
+            Ok(())
+        },
+        Some("fen") => {
+            game.build_board_from_fen_string(&fen_tokens.join(" "))
+                .map_err(|fen_error| format!("Invalid fen string: {}", fen_error))
+        },
+        Some(argument) => Err(format!("Unrecognised position argument: {}", argument)),
+        None           => Err("Missing position argument".to_string()),
+    };
+
+    if let Err(message) = position_result {
+        println!("info string {}", message);
+        return;
+    }
 
     for next_move in moves {
-        game.accept_move(next_move);
+        if !game.accept_move(next_move) {
+            println!("info string Illegal move: {}", next_move);
+            break;
+        }
     }
 }
 
-fn paraphrase_startpos_token(game: &mut Game, token_sequence: &mut SplitWhitespace) {
-    let mut moves = Vec::new();
+fn go(game: &mut Game, token_sequence: &mut SplitWhitespace) {
+    let mut search_limits = SearchLimits::default();
 
     while let Some(token) = token_sequence.next() {
         match token {
-            "moves" => {
-                while let Some(chess_move) = token_sequence.next() {
-                    moves.push(chess_move.to_string());
-                }
-            },
-            _ => {},
+            "wtime"     => search_limits.white_time = parse_numeric_argument(token_sequence),
+            "btime"     => search_limits.black_time = parse_numeric_argument(token_sequence),
+            "winc"      => search_limits.white_increment = parse_numeric_argument(token_sequence),
+            "binc"      => search_limits.black_increment = parse_numeric_argument(token_sequence),
+            "movestogo" => search_limits.moves_to_go = parse_numeric_argument(token_sequence),
+            "movetime"  => search_limits.move_time = parse_numeric_argument(token_sequence),
+            "depth"     => search_limits.depth = parse_numeric_argument(token_sequence).map(|depth| depth as usize),
+            "nodes"     => search_limits.nodes = parse_numeric_argument(token_sequence),
+            "infinite"  => search_limits.infinite = true,
+            "perft"     => return perft(game, token_sequence),
+            _           => {},
         }
     }
 
-    game.setup_initial_position();
+    // The GUI still gets its bestmove, the null move, so it doesn't wait forever.
+    if !game.has_valid_position() {
+        println!("info string No valid position to search");
+    }
 
-    for next_move in moves {
-        game.accept_move(next_move);
+    game.start_search(search_limits, print_search_info, |best_move| println!("bestmove {}", format_move(best_move)));
+}
+
+fn print_search_info(search_info: &SearchInfo) {
+    let time = search_info.time.as_millis() as u64;
+
+    let principal_variation: Vec<String> = search_info.principal_variation.iter()
+        .map(|chess_move| chess_move.to_string())
+        .collect();
+
+    println!("info depth {} score {} nodes {} nps {} time {} pv {}", search_info.depth,
+             format_score(search_info.score), search_info.nodes, get_nodes_per_second(search_info.nodes, search_info.time),
+             time, principal_variation.join(" "));
+}
+
+// UCI wants the null move "0000" when there is no move at all.
+fn format_move(chess_move: Option<Move>) -> String {
+    chess_move.map_or("0000".to_string(), |chess_move| chess_move.to_string())
+}
+
+fn format_score(score: Score) -> String {
+    match score {
+        Score::Centipawns(centipawns) => format!("cp {}", centipawns),
+        Score::Mate(moves)            => format!("mate {}", moves),
     }
 }
 
-fn go(game: &mut Game) {
-    game.find_best_move();
+fn get_nodes_per_second(nodes: u64, time: Duration) -> u64 {
+    nodes * 1000 / max(time.as_millis() as u64, 1)
+}
+
+fn parse_numeric_argument(token_sequence: &mut SplitWhitespace) -> Option<u64> {
+    // GUIs may send negative clock values when a player is in time trouble.
+    token_sequence.next()
+        .and_then(|token| token.parse::<i64>().ok())
+        .map(|value| max(value, 0) as u64)
 }
 
-fn set_option() {
+fn bench(game: &mut Game, token_sequence: &mut SplitWhitespace) {
+    game.stop_search();
+
+    let depth = parse_numeric_argument(token_sequence).map_or(DEFAULT_BENCH_DEPTH, |depth| depth as usize);
 
-}
\ No newline at end of file
+    let start_time = Instant::now();
+    let total_nodes = run_bench(depth, |fen_string, best_move, nodes| {
+        println!("info string {} bestmove {} nodes {}", fen_string, format_move(best_move), nodes);
+    });
+    let time = start_time.elapsed();
+
+    println!("info string bench depth {} nodes {} time {} nps {}", depth, total_nodes, time.as_millis(),
+             get_nodes_per_second(total_nodes, time));
+}
+
+fn perft(game: &mut Game, token_sequence: &mut SplitWhitespace) {
+    if !game.has_valid_position() {
+        println!("info string No valid position to count");
+        return;
+    }
+
+    match parse_numeric_argument(token_sequence) {
+        Some(depth) => {
+            let start_time = Instant::now();
+            let node_counts = game.run_perft(depth as usize);
+            let time = start_time.elapsed();
+
+            for &(some_move, partial_nodes) in node_counts.iter() {
+                println!("{}: {}", some_move, partial_nodes);
+            }
+
+            let nodes = if depth == 0 {
+                1
+            } else {
+                node_counts.iter().map(|&(_, partial_nodes)| partial_nodes).sum()
+            };
+
+            println!();
+            println!("Nodes searched: {}", nodes);
+            println!("Time: {} ms", time.as_millis());
+            println!("Nodes per second: {}", get_nodes_per_second(nodes as u64, time));
+        },
+        None => println!("info string Missing perft depth"),
+    }
+}
+
+fn set_option(game: &mut Game, token_sequence: &mut SplitWhitespace) {
+    let mut name = Vec::new();
+    let mut value = Vec::new();
+    let mut reading_value = false;
+
+    for token in token_sequence {
+        match token {
+            "name"  => reading_value = false,
+            "value" => reading_value = true,
+            _ if reading_value => value.push(token),
+            _       => name.push(token),
+        }
+    }
+
+    match name.join(" ").to_lowercase().as_str() {
+        "hash" => {
+            match value.join(" ").parse::<usize>() {
+                Ok(size_in_megabytes) => game.set_hash_size(size_in_megabytes),
+                Err(_)                => println!("info string Invalid Hash value: {}", value.join(" ")),
+            }
+        },
+        _ => println!("info string Unrecognised option: {}", name.join(" ")),
+    }
+}
+#[cfg(test)]
+mod uci_interface_tests {
+    use std::sync::mpsc::channel;
+    use chess_move::Move;
+    use game::Game;
+    use searcher::SearchLimits;
+    use uci_interface::execute_command;
+
+    fn search_best_move(game: &mut Game) -> Option<Move> {
+        let (sender, receiver) = channel();
+
+        game.start_search(SearchLimits { depth: Some(2), ..SearchLimits::default() }, |_| {},
+                          move |best_move| sender.send(best_move).unwrap());
+
+        receiver.recv().unwrap()
+    }
+
+    #[test]
+    fn a_game_starts_from_the_initial_position() {
+        let mut game = Game::new();
+
+        assert!(execute_command(&mut game, "go depth 1"));
+        assert!(search_best_move(&mut game).is_some());
+        assert!(!execute_command(&mut game, "quit"));
+    }
+
+    #[test]
+    fn searching_after_a_bad_fen_reports_no_move() {
+        let mut game = Game::new();
+
+        assert!(execute_command(&mut game, "position startpos moves e2e4"));
+        assert!(execute_command(&mut game, "position fen 8/8/8 w - -"));
+        assert!(!game.has_valid_position());
+
+        // Neither the old position nor an empty board gets searched.
+        assert!(execute_command(&mut game, "go depth 2"));
+        assert_eq!(search_best_move(&mut game), None);
+
+        assert!(execute_command(&mut game, "position fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1 moves e1g1"));
+        assert!(game.has_valid_position());
+        assert!(search_best_move(&mut game).is_some());
+
+        assert!(!execute_command(&mut game, "quit"));
+    }
+}